@@ -0,0 +1,275 @@
+mod inputs;
+
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use common::{InputError, Json, Solution};
+
+use crate::cli::{BenchOptions, InputSet};
+use crate::days::DAYS;
+
+/// Timing samples of the measured phases, along with their names
+pub type Samples = Vec<(&'static str, Vec<Duration>)>;
+
+/// Runs `f` the given number of times, timing every run
+fn sample<T>(iterations: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// Times parsing and each part of the solution separately.
+///
+/// Parts that are only found together are timed as a single "both" phase.
+pub fn measure<S: Solution>(input: &str, iterations: usize) -> Result<Samples, InputError> {
+    // Parsing once upfront also serves as a warm-up
+    let parsed = S::parse(input)?;
+
+    let mut samples = vec![("parse", sample(iterations, || S::parse(black_box(input))))];
+    if S::SHARED_SOLVER {
+        samples.push(("both", sample(iterations, || S::solve(black_box(&parsed)))));
+    } else {
+        samples.push(("part 1", sample(iterations, || S::part_1(black_box(&parsed)))));
+        samples.push(("part 2", sample(iterations, || S::part_2(black_box(&parsed)))));
+    }
+    Ok(samples)
+}
+
+/// Statistics of the timing samples, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut nanos = samples
+            .iter()
+            .map(|sample| sample.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        nanos.sort_by(f64::total_cmp);
+
+        let count = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / count;
+        let median = if nanos.len() % 2 == 0 {
+            (nanos[nanos.len() / 2 - 1] + nanos[nanos.len() / 2]) / 2.0
+        } else {
+            nanos[nanos.len() / 2]
+        };
+        let stddev = if nanos.len() > 1 {
+            (nanos.iter().map(|nanos| (nanos - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        Stats { mean, median, stddev }
+    }
+}
+
+/// Timings of a single phase of a day for one of the input sets
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub day: u8,
+    pub input: String,
+    pub phase: String,
+    pub stats: Stats,
+}
+
+impl BenchResult {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("day", Json::from(self.day as u64)),
+            ("input", Json::from(self.input.as_str())),
+            ("phase", Json::from(self.phase.as_str())),
+            ("mean_ns", Json::from(self.stats.mean)),
+            ("median_ns", Json::from(self.stats.median)),
+            ("stddev_ns", Json::from(self.stats.stddev)),
+        ])
+    }
+
+    fn from_json(value: &Json) -> Option<Self> {
+        Some(BenchResult {
+            day: value.get("day")?.as_u64()? as u8,
+            input: value.get("input")?.as_str()?.to_string(),
+            phase: value.get("phase")?.as_str()?.to_string(),
+            stats: Stats {
+                mean: value.get("mean_ns")?.as_f64()?,
+                median: value.get("median_ns")?.as_f64()?,
+                stddev: value.get("stddev_ns")?.as_f64()?,
+            },
+        })
+    }
+
+    fn is_same_benchmark(&self, other: &BenchResult) -> bool {
+        self.day == other.day && self.input == other.input && self.phase == other.phase
+    }
+}
+
+pub fn save_baseline(path: &Path, iterations: usize, results: &[BenchResult]) -> Result<(), String> {
+    let baseline = Json::object([
+        ("iterations", Json::from(iterations as u64)),
+        (
+            "results",
+            Json::Array(results.iter().map(BenchResult::to_json).collect()),
+        ),
+    ]);
+    fs::write(path, baseline.pretty() + "\n")
+        .map_err(|e| format!("failed to save the baseline to '{}': {}", path.display(), e))
+}
+
+pub fn load_baseline(path: &Path) -> Result<Vec<BenchResult>, String> {
+    let path_str = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read the baseline '{}': {}", path_str, e))?;
+    let baseline = Json::parse(&text).map_err(|e| e.render(&path_str, &text))?;
+
+    baseline
+        .get("results")
+        .and_then(Json::as_array)
+        .ok_or_else(|| format!("baseline '{}' has no results", path_str))?
+        .iter()
+        .map(|result| {
+            BenchResult::from_json(result).ok_or_else(|| format!("baseline '{}' has a malformed result", path_str))
+        })
+        .collect()
+}
+
+/// Change of the median compared to the baseline in percent, `None` if the baseline's median is zero.
+///
+/// Medians are compared, as they are less affected by outliers.
+fn relative_change(previous_median: f64, median: f64) -> Option<f64> {
+    // Trivial phases can take no measurable time on a coarse clock
+    (previous_median > 0.0).then(|| (median - previous_median) / previous_median * 100.0)
+}
+
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.2?}", Duration::from_secs_f64(nanos / 1e9))
+}
+
+/// Runs the benchmarks, returns `false` if any of them regressed compared to the baseline
+pub fn run(options: &BenchOptions) -> Result<bool, String> {
+    let baseline = options.baseline.as_deref().map(load_baseline).transpose()?;
+
+    println!(
+        "{:>3}  {:<6}  {:<6}  {:>10}  {:>10}  {:>10}",
+        "day", "input", "phase", "mean", "median", "stddev"
+    );
+
+    let mut results = Vec::new();
+    let mut regressions = 0;
+    for day in DAYS
+        .iter()
+        .filter(|day| options.day.is_none_or(|number| number == day.number))
+    {
+        for input_set in options.input_sets.iter() {
+            let (input_name, input) = match input_set {
+                InputSet::Sample => ("sample", inputs::sample(day.number).map(str::to_string)),
+                InputSet::Large => ("large", inputs::large(day.number)),
+            };
+            let Some(input) = input else {
+                continue;
+            };
+
+            let samples = (day.bench)(&input, options.iterations)
+                .map_err(|e| e.render(&format!("<{} input of day {}>", input_name, day.number), &input))?;
+
+            for (phase, samples) in samples.iter() {
+                let result = BenchResult {
+                    day: day.number,
+                    input: input_name.to_string(),
+                    phase: phase.to_string(),
+                    stats: Stats::from_samples(samples),
+                };
+
+                let mut line = format!(
+                    "{:>3}  {:<6}  {:<6}  {:>10}  {:>10}  {:>10}",
+                    result.day,
+                    result.input,
+                    result.phase,
+                    format_nanos(result.stats.mean),
+                    format_nanos(result.stats.median),
+                    format_nanos(result.stats.stddev)
+                );
+                let previous = baseline
+                    .as_ref()
+                    .and_then(|baseline| baseline.iter().find(|previous| previous.is_same_benchmark(&result)));
+                match previous.and_then(|previous| relative_change(previous.stats.median, result.stats.median)) {
+                    Some(change) => {
+                        line.push_str(&format!("  {:+.1}%", change));
+                        if change > options.threshold {
+                            line.push_str(" REGRESSION");
+                            regressions += 1;
+                        }
+                    }
+                    None if previous.is_some() => line.push_str("  n/a"),
+                    None => {}
+                }
+                println!("{}", line);
+
+                results.push(result);
+            }
+        }
+    }
+
+    if let Some(path) = options.save_baseline.as_deref() {
+        save_baseline(path, options.iterations, &results)?;
+        println!("Saved the baseline to '{}'", path.display());
+    }
+
+    if baseline.is_some() {
+        if regressions == 0 {
+            println!("No regressions over {}%", options.threshold);
+        } else {
+            println!("{} regression(s) over {}%", regressions, options.threshold);
+        }
+    }
+
+    Ok(regressions == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::bench::inputs::{large, sample};
+    use crate::bench::{relative_change, Stats};
+    use crate::days::DAYS;
+
+    #[test]
+    fn calculates_stats() {
+        let samples = [10, 20, 30, 60].map(Duration::from_nanos);
+
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.mean, 30.0);
+        assert_eq!(stats.median, 25.0);
+        assert!((stats.stddev - 21.602).abs() < 0.001);
+    }
+
+    #[test]
+    fn compares_medians() {
+        assert_eq!(relative_change(200.0, 250.0), Some(25.0));
+        assert_eq!(relative_change(200.0, 100.0), Some(-50.0));
+        assert_eq!(relative_change(0.0, 100.0), None);
+        assert_eq!(relative_change(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn generated_inputs_are_valid() {
+        for day in DAYS.iter() {
+            // Freshly scaffolded days don't have any inputs yet
+            for input in [sample(day.number).map(str::to_string), large(day.number)]
+                .into_iter()
+                .flatten()
+            {
+                (day.bench)(&input, 0).unwrap_or_else(|e| panic!("{}", e.render("input", &input)));
+            }
+        }
+    }
+}
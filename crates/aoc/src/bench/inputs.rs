@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use common::Rng;
+
+/// Returns the sample input from the puzzle description of the given day
+pub fn sample(day: u8) -> Option<&'static str> {
+    let sample = match day {
+        1 => "3   4\n4   3\n2   5\n1   3\n3   9\n3   3",
+        2 => "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9",
+        3 => "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+        4 => "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\nXXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX",
+        5 => "47|53\n97|13\n97|61\n97|47\n75|29\n61|13\n75|53\n29|13\n97|29\n53|29\n61|53\n97|53\n61|29\n47|13\n75|47\n97|75\n47|61\n75|61\n47|29\n75|13\n53|13\n\n75,47,61,53,29\n97,61,53,29,13\n75,29,13\n75,97,47,61,53\n61,13,29\n97,13,75,29,47",
+        6 => "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...",
+        7 => "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20",
+        8 => "............\n........0...\n.....0......\n.......0....\n....0.......\n......A.....\n............\n............\n........A...\n.........A..\n............\n............",
+        9 => "2333133121414131402",
+        10 => "89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732",
+        11 => "125 17",
+        12 => "RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\nVVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE",
+        _ => return None,
+    };
+    Some(sample)
+}
+
+/// Generates a synthetic input of roughly the same size as a real puzzle input for the given day.
+///
+/// Inputs are generated from a fixed seed, so that timings of different runs can be compared.
+pub fn large(day: u8) -> Option<String> {
+    let mut rng = Rng::new(2024 * 100 + day as u64);
+    let input = match day {
+        1 => generate_lists(&mut rng),
+        2 => generate_reports(&mut rng),
+        3 => generate_memory(&mut rng),
+        4 => generate_grid(&mut rng, 140, |rng, _| *rng.choose(b"XMAS")),
+        5 => generate_rules_and_updates(&mut rng),
+        6 => generate_lab_map(&mut rng),
+        7 => generate_equations(&mut rng),
+        8 => generate_antenna_map(&mut rng),
+        9 => generate_disk_map(&mut rng),
+        10 => generate_grid(&mut rng, 50, |rng, (row, col)| {
+            // Diagonal slopes produce a lot of hiking trails
+            b'0' + ((row + col + rng.index(2)) % 10) as u8
+        }),
+        11 => generate_stones(&mut rng),
+        12 => generate_garden(&mut rng),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn generate_grid(rng: &mut Rng, size: usize, mut cell: impl FnMut(&mut Rng, (usize, usize)) -> u8) -> String {
+    let mut grid = String::with_capacity((size + 1) * size);
+    for row in 0..size {
+        for col in 0..size {
+            grid.push(cell(rng, (row, col)) as char);
+        }
+        grid.push('\n');
+    }
+    grid
+}
+
+fn generate_lists(rng: &mut Rng) -> String {
+    let mut input = String::new();
+    let mut left_items = Vec::new();
+    for _ in 0..1000 {
+        let left = rng.range(10000..100000);
+        left_items.push(left);
+        // Make some of the items appear in both lists
+        let right = if rng.chance(0.3) {
+            *rng.choose(&left_items)
+        } else {
+            rng.range(10000..100000)
+        };
+        let _ = writeln!(input, "{}   {}", left, right);
+    }
+    input
+}
+
+fn generate_reports(rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..1000 {
+        let len = rng.range(5..9) as usize;
+        let direction = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.range(20..80);
+        let mut levels = Vec::with_capacity(len);
+        for _ in 0..len {
+            levels.push(level);
+            level += direction * rng.range(1..4);
+        }
+        if rng.chance(0.4) {
+            // Break the report, some of them can be fixed by removing a level
+            let idx = rng.index(len);
+            levels[idx] = rng.range(1..100);
+        }
+        let levels = levels.iter().map(|level| level.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(input, "{}", levels.join(" "));
+    }
+    input
+}
+
+fn generate_memory(rng: &mut Rng) -> String {
+    const GARBAGE: &[u8] = b"mul()do'nt,[]{}<>!@#$%^&*:?;+-_ 0123456789";
+
+    let mut input = String::new();
+    for _ in 0..6 {
+        let mut line = String::new();
+        while line.len() < 3000 {
+            let roll = rng.index(100);
+            match roll {
+                0..70 => line.push(*rng.choose(GARBAGE) as char),
+                70..85 => {
+                    let _ = write!(line, "mul({},{})", rng.range(1..1000), rng.range(1..1000));
+                }
+                85..90 => line.push_str("do()"),
+                90..95 => line.push_str("don't()"),
+                _ => {
+                    let _ = write!(line, "mul({},{}]", rng.range(1..1000), rng.range(1..1000));
+                }
+            }
+        }
+        input.push_str(&line);
+        input.push('\n');
+    }
+    input
+}
+
+fn generate_rules_and_updates(rng: &mut Rng) -> String {
+    let mut pages = (10..100).collect::<Vec<u64>>();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+
+    // Rules are derived from a total order of the pages, so every update can be fixed
+    let mut rules = Vec::new();
+    for (idx, page) in pages.iter().enumerate() {
+        for later_page in pages[idx + 1..].iter() {
+            rules.push(format!("{}|{}", page, later_page));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut input = rules.join("\n");
+    input.push_str("\n\n");
+    for _ in 0..200 {
+        let len = rng.range(2..12) as usize * 2 + 1;
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        let update = update[..len].iter().map(|page| page.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(input, "{}", update.join(","));
+    }
+    input
+}
+
+/// Returns the number of steps the guard takes before leaving the map, or `None` if the guard never leaves it
+fn guard_route_len(map: &[Vec<u8>], start: (i64, i64)) -> Option<usize> {
+    let offsets = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+    let mut seen = HashSet::new();
+    let (mut position, mut direction) = (start, 0);
+    while seen.insert((position, direction)) {
+        let next = (position.0 + offsets[direction].0, position.1 + offsets[direction].1);
+        let Some(cell) = usize::try_from(next.0)
+            .ok()
+            .zip(usize::try_from(next.1).ok())
+            .and_then(|(row, col)| map.get(row)?.get(col))
+        else {
+            return Some(seen.len());
+        };
+        if *cell == b'#' {
+            direction = (direction + 1) % 4;
+        } else {
+            position = next;
+        }
+    }
+    None
+}
+
+fn generate_lab_map(rng: &mut Rng) -> String {
+    const SIZE: usize = 130;
+
+    // Random maps tend to have short patrol routes, so the one with the longest route is picked to get meaningful
+    // timings. Solutions also expect the patrol route to end, so maps where the guard never leaves are skipped.
+    let mut best_map = None;
+    let mut best_route_len = 0;
+    for _ in 0..100 {
+        let mut map = vec![vec![b'.'; SIZE]; SIZE];
+        for row in map.iter_mut() {
+            for cell in row.iter_mut() {
+                if rng.chance(0.03) {
+                    *cell = b'#';
+                }
+            }
+        }
+        let start = (rng.range(40..90), rng.range(40..90));
+        map[start.0 as usize][start.1 as usize] = b'^';
+
+        if let Some(route_len) = guard_route_len(&map, start).filter(|len| *len > best_route_len) {
+            best_route_len = route_len;
+            best_map = Some(map);
+        }
+    }
+
+    best_map
+        .expect("at least one of the maps has an exit")
+        .into_iter()
+        .map(|row| String::from_utf8(row).expect("map consists of ASCII characters") + "\n")
+        .collect()
+}
+
+fn generate_equations(rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..850 {
+        // Small numbers keep all possible results within u64
+        let numbers = (0..rng.range(3..9)).map(|_| rng.range(1..100)).collect::<Vec<_>>();
+        let mut test_value = numbers[0];
+        for number in numbers[1..].iter() {
+            test_value = match rng.index(3) {
+                0 => test_value + number,
+                1 => test_value * number,
+                _ => format!("{}{}", test_value, number)
+                    .parse()
+                    .expect("concatenation is a number"),
+            };
+        }
+        if rng.chance(0.5) {
+            // Most likely not a valid equation anymore
+            test_value += 1;
+        }
+        let numbers = numbers.iter().map(|number| number.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(input, "{}: {}", test_value, numbers.join(" "));
+    }
+    input
+}
+
+fn generate_antenna_map(rng: &mut Rng) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    let frequencies = &FREQUENCIES[..rng.range(30..40) as usize];
+    generate_grid(rng, 50, |rng, _| {
+        if rng.chance(0.08) {
+            *rng.choose(frequencies)
+        } else {
+            b'.'
+        }
+    })
+}
+
+fn generate_disk_map(rng: &mut Rng) -> String {
+    let mut input = (0..19999)
+        .map(|idx| {
+            let min_size = if idx % 2 == 0 { 1 } else { 0 };
+            char::from(b'0' + rng.range(min_size..10) as u8)
+        })
+        .collect::<String>();
+    // Make sure that there is at least a single free slot
+    input.replace_range(1..2, "5");
+    input.push('\n');
+    input
+}
+
+fn generate_stones(rng: &mut Rng) -> String {
+    let stones = (0..8).map(|_| rng.range(0..1000000).to_string()).collect::<Vec<_>>();
+    stones.join(" ") + "\n"
+}
+
+fn generate_garden(rng: &mut Rng) -> String {
+    const SIZE: usize = 140;
+
+    let mut garden = vec![vec![b'A'; SIZE]; SIZE];
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            // Copying the neighbours forms regions of various shapes
+            garden[row][col] = match rng.index(10) {
+                0..6 if col > 0 => garden[row][col - 1],
+                6..9 if row > 0 => garden[row - 1][col],
+                _ => b'A' + rng.index(26) as u8,
+            };
+        }
+    }
+    garden
+        .into_iter()
+        .map(|row| String::from_utf8(row).expect("garden consists of ASCII characters") + "\n")
+        .collect()
+}
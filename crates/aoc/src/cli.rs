@@ -0,0 +1,424 @@
+use std::env;
+use std::path::PathBuf;
+
+use common::{default_inputs_dir, Format};
+
+pub const USAGE: &str = "Usage:
+    aoc run --day <DAY> [--part <PART>] [--format <text|json>] [INPUT | -]...
+    aoc run --all [--inputs <DIR>] [--format <text|json>]
+    aoc bench [--day <DAY>] [--input <sample|large>] [--iterations <N>]
+              [--save-baseline <FILE>] [--baseline <FILE>] [--threshold <PERCENT>]
+    aoc verify [--answers <FILE>] [--inputs <DIR>] [--record]
+    aoc new-day --day <DAY> [--root <DIR>]
+    aoc fetch --day <DAY> [--year <YEAR>] [--cache <DIR>] [--base-url <URL>] [--session-file <FILE>]
+
+Options:
+    --day <DAY>               Day to run (1-25)
+    --part <PART>             Only print the answer for the given part (1 or 2)
+    --all                     Run every available day
+    --format <text|json>      Print the answers as text or as a JSON object per day [default: text]
+    --inputs <DIR>            Directory with `day-NN.txt` input files [default: $AOC_INPUT_DIR or inputs]
+    --input <sample|large>    Only benchmark the sample or the large synthetic inputs
+    --iterations <N>          Number of times each phase is measured [default: 10]
+    --save-baseline <FILE>    Save the timings to a JSON file
+    --baseline <FILE>         Compare the timings with a previously saved baseline
+    --threshold <PERCENT>     Median slowdown reported as a regression [default: 10]
+    --answers <FILE>          JSON file with the expected answers [default: answers.json]
+    --record                  Save answers of the inputs that have none recorded yet
+    --year <YEAR>             Year of the puzzle to fetch the input for [default: 2024]
+    --cache <DIR>             Directory with the downloaded inputs [default: $AOC_CACHE_DIR or .aoc-cache]
+    --base-url <URL>          URL of the puzzle server [default: $AOC_BASE_URL or https://adventofcode.com]
+    --session-file <FILE>     File with the session token [default: $AOC_SESSION or ~/.config/aoc/session]
+    --root <DIR>              Root of the workspace to add the day to [default: found from the current directory]";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InputSet {
+    /// Sample input from the puzzle description
+    Sample,
+    /// Synthetic input of the size of a real puzzle input
+    Large,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub day: Option<u8>,
+    pub input_sets: Vec<InputSet>,
+    pub iterations: usize,
+    pub save_baseline: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub threshold: f64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyOptions {
+    pub answers: PathBuf,
+    pub inputs_dir: PathBuf,
+    pub record: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub day: u8,
+    pub year: u16,
+    pub cache_dir: PathBuf,
+    pub base_url: String,
+    /// Read the token from `$AOC_SESSION` or the default config file if `None`
+    pub session_file: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewDayOptions {
+    pub day: u8,
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DaySelection {
+    /// Every input is solved separately, the day's input from the inputs directory is used if there are none
+    Single {
+        day: u8,
+        inputs: Vec<PathBuf>,
+    },
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run {
+        selection: DaySelection,
+        part: Option<u8>,
+        inputs_dir: PathBuf,
+        format: Format,
+    },
+    Bench(BenchOptions),
+    Verify(VerifyOptions),
+    Fetch(FetchOptions),
+    NewDay(NewDayOptions),
+}
+
+impl Command {
+    /// Parses the command line arguments (without the binary name)
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        match args.next().as_deref() {
+            Some("run") => parse_run(args),
+            Some("bench") => parse_bench(args),
+            Some("verify") => parse_verify(args),
+            Some("fetch") => parse_fetch(args),
+            Some("new-day") => parse_new_day(args),
+            Some(command) => Err(format!("unknown command '{}'", command)),
+            None => Err("command is missing".to_string()),
+        }
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut all = false;
+    let mut inputs = Vec::new();
+    let mut inputs_dir = default_inputs_dir();
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(args.next(), "--day", 1..=25)?),
+            "--part" => part = Some(parse_number(args.next(), "--part", 1..=2)?),
+            "--all" => all = true,
+            "--format" => {
+                format = args
+                    .next()
+                    .ok_or("--format requires either 'text' or 'json'")?
+                    .parse()?
+            }
+            "--inputs" => inputs_dir = args.next().map(PathBuf::from).ok_or("--inputs requires a directory")?,
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            path => inputs.push(PathBuf::from(path)),
+        }
+    }
+
+    let selection = match (day, all) {
+        (Some(_), true) => return Err("--day and --all can't be used together".to_string()),
+        (None, false) => return Err("either --day or --all is required".to_string()),
+        (Some(day), false) => DaySelection::Single { day, inputs },
+        (None, true) => {
+            if !inputs.is_empty() {
+                return Err("input file can't be provided with --all, use --inputs instead".to_string());
+            }
+            DaySelection::All
+        }
+    };
+
+    Ok(Command::Run {
+        selection,
+        part,
+        inputs_dir,
+        format,
+    })
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut options = BenchOptions {
+        day: None,
+        input_sets: vec![InputSet::Sample, InputSet::Large],
+        iterations: 10,
+        save_baseline: None,
+        baseline: None,
+        threshold: 10.0,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(args.next(), "--day", 1..=25)?),
+            "--input" => {
+                options.input_sets = match args.next().as_deref() {
+                    Some("sample") => vec![InputSet::Sample],
+                    Some("large") => vec![InputSet::Large],
+                    _ => return Err("--input requires either 'sample' or 'large'".to_string()),
+                }
+            }
+            "--iterations" => {
+                options.iterations = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|iterations| *iterations > 0)
+                    .ok_or("--iterations requires a positive number")?
+            }
+            "--save-baseline" => {
+                options.save_baseline = Some(
+                    args.next()
+                        .map(PathBuf::from)
+                        .ok_or("--save-baseline requires a file")?,
+                )
+            }
+            "--baseline" => {
+                options.baseline = Some(args.next().map(PathBuf::from).ok_or("--baseline requires a file")?)
+            }
+            "--threshold" => {
+                options.threshold = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|threshold: &f64| *threshold >= 0.0)
+                    .ok_or("--threshold requires a non-negative percentage")?
+            }
+            arg => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Bench(options))
+}
+
+fn parse_verify(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut options = VerifyOptions {
+        answers: PathBuf::from("answers.json"),
+        inputs_dir: default_inputs_dir(),
+        record: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => options.answers = args.next().map(PathBuf::from).ok_or("--answers requires a file")?,
+            "--inputs" => options.inputs_dir = args.next().map(PathBuf::from).ok_or("--inputs requires a directory")?,
+            "--record" => options.record = true,
+            arg => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Verify(options))
+}
+
+fn parse_fetch(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day = None;
+    let mut year = 2024;
+    let mut cache_dir = env::var_os("AOC_CACHE_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| PathBuf::from(".aoc-cache"), PathBuf::from);
+    let mut base_url = env::var("AOC_BASE_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| "https://adventofcode.com".to_string());
+    let mut session_file = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(args.next(), "--day", 1..=25)?),
+            "--year" => {
+                year = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|year| *year >= 2015)
+                    .ok_or("--year requires a year since 2015")?
+            }
+            "--cache" => cache_dir = args.next().map(PathBuf::from).ok_or("--cache requires a directory")?,
+            "--base-url" => base_url = args.next().ok_or("--base-url requires a URL")?,
+            "--session-file" => {
+                session_file = Some(args.next().map(PathBuf::from).ok_or("--session-file requires a file")?)
+            }
+            arg => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Fetch(FetchOptions {
+        day: day.ok_or("--day is required")?,
+        year,
+        cache_dir,
+        base_url,
+        session_file,
+    }))
+}
+
+fn parse_new_day(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day = None;
+    let mut root = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(args.next(), "--day", 1..=25)?),
+            "--root" => root = Some(args.next().map(PathBuf::from).ok_or("--root requires a directory")?),
+            arg => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+
+    Ok(Command::NewDay(NewDayOptions {
+        day: day.ok_or("--day is required")?,
+        root,
+    }))
+}
+
+fn parse_number(value: Option<String>, flag: &str, range: std::ops::RangeInclusive<u8>) -> Result<u8, String> {
+    value
+        .and_then(|value| value.parse::<u8>().ok())
+        .filter(|value| range.contains(value))
+        .ok_or_else(|| {
+            format!(
+                "{} requires a number between {} and {}",
+                flag,
+                range.start(),
+                range.end()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use common::{default_inputs_dir, Format};
+
+    use crate::cli::{BenchOptions, Command, DaySelection, FetchOptions, InputSet, NewDayOptions, VerifyOptions};
+
+    fn parse(args: &str) -> Result<Command, String> {
+        Command::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parses_single_day() {
+        assert_eq!(
+            parse("run --day 7 --part 2 inputs/07.txt"),
+            Ok(Command::Run {
+                selection: DaySelection::Single {
+                    day: 7,
+                    inputs: vec![PathBuf::from("inputs/07.txt")]
+                },
+                part: Some(2),
+                inputs_dir: default_inputs_dir(),
+                format: Format::Text,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_multiple_inputs() {
+        assert_eq!(
+            parse("run --day 3 inputs/03.txt - --format json other/03.txt"),
+            Ok(Command::Run {
+                selection: DaySelection::Single {
+                    day: 3,
+                    inputs: ["inputs/03.txt", "-", "other/03.txt"].map(PathBuf::from).to_vec(),
+                },
+                part: None,
+                inputs_dir: default_inputs_dir(),
+                format: Format::Json,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_all_days() {
+        assert_eq!(
+            parse("run --all --inputs data --format json"),
+            Ok(Command::Run {
+                selection: DaySelection::All,
+                part: None,
+                inputs_dir: PathBuf::from("data"),
+                format: Format::Json,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_bench_options() {
+        assert_eq!(
+            parse("bench --day 6 --input large --iterations 3 --baseline base.json --threshold 5"),
+            Ok(Command::Bench(BenchOptions {
+                day: Some(6),
+                input_sets: vec![InputSet::Large],
+                iterations: 3,
+                save_baseline: None,
+                baseline: Some(PathBuf::from("base.json")),
+                threshold: 5.0,
+            }))
+        );
+        assert!(parse("bench --iterations 0").is_err());
+        assert!(parse("bench --input huge").is_err());
+    }
+
+    #[test]
+    fn parses_verify_options() {
+        assert_eq!(
+            parse("verify --answers data/answers.json --record"),
+            Ok(Command::Verify(VerifyOptions {
+                answers: PathBuf::from("data/answers.json"),
+                inputs_dir: default_inputs_dir(),
+                record: true,
+            }))
+        );
+        assert!(parse("verify --answers").is_err());
+    }
+
+    #[test]
+    fn parses_fetch_options() {
+        assert_eq!(
+            parse("fetch --day 3 --year 2023 --cache cache --base-url http://127.0.0.1:8080 --session-file token"),
+            Ok(Command::Fetch(FetchOptions {
+                day: 3,
+                year: 2023,
+                cache_dir: PathBuf::from("cache"),
+                base_url: "http://127.0.0.1:8080".to_string(),
+                session_file: Some(PathBuf::from("token")),
+            }))
+        );
+        assert!(parse("fetch").is_err());
+        assert!(parse("fetch --day 3 --year 1999").is_err());
+    }
+
+    #[test]
+    fn parses_new_day_options() {
+        assert_eq!(
+            parse("new-day --day 13"),
+            Ok(Command::NewDay(NewDayOptions { day: 13, root: None }))
+        );
+        assert!(parse("new-day --day 26").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse("run").is_err());
+        assert!(parse("run --day 7 --all").is_err());
+        assert!(parse("run --day 26").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --all input.txt").is_err());
+        assert!(parse("run --all --format xml").is_err());
+        assert!(parse("solve --day 1").is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use common::{InputError, Report, Solution};
+
+use crate::bench::{self, Samples};
+
+/// A single puzzle day that can be run by the runner
+pub struct Day {
+    pub number: u8,
+    /// Solves the puzzle for the provided input, either both parts or only the selected one
+    pub solve: fn(&str, Option<u8>) -> Result<Report, InputError>,
+    /// Measures parsing and both parts separately for the given number of iterations
+    pub bench: fn(&str, usize) -> Result<Samples, InputError>,
+}
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        number: S::DAY,
+        solve: Report::solve::<S>,
+        bench: bench::measure::<S>,
+    }
+}
+
+pub const DAYS: [Day; 12] = [
+    day::<day_1::Day1>(),
+    day::<day_2::Day2>(),
+    day::<day_3::Day3>(),
+    day::<day_4::Day4>(),
+    day::<day_5::Day5>(),
+    day::<day_6::Day6>(),
+    day::<day_7::Day7>(),
+    day::<day_8::Day8>(),
+    day::<day_9::Day9>(),
+    day::<day_10::Day10>(),
+    day::<day_11::Day11>(),
+    day::<day_12::Day12>(),
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,151 @@
+mod http;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::input_path;
+
+use crate::cli::FetchOptions;
+
+/// Environment variable with the session token
+const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Path of the cached input for the given puzzle
+pub fn cached_input_path(cache_dir: &Path, year: u16, day: u8) -> PathBuf {
+    input_path(&cache_dir.join(year.to_string()), day)
+}
+
+/// Returns the session token, either from the explicitly provided file, from `$AOC_SESSION` or from the default
+/// config file (`~/.config/aoc/session`)
+fn session_token(session_file: Option<&Path>) -> Result<String, String> {
+    let default_file =
+        || std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("aoc").join("session"));
+    let file = match session_file {
+        Some(file) => file.to_path_buf(),
+        None => match std::env::var(SESSION_VAR) {
+            Ok(token) if !token.trim().is_empty() => return Ok(token.trim().to_string()),
+            _ => default_file().ok_or_else(|| format!("session token is missing, set ${}", SESSION_VAR))?,
+        },
+    };
+
+    let token = fs::read_to_string(&file).map_err(|e| {
+        format!(
+            "failed to read the session token from '{}': {} (set ${} or use --session-file)",
+            file.display(),
+            e,
+            SESSION_VAR
+        )
+    })?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(format!("session file '{}' is empty", file.display()));
+    }
+    Ok(token.to_string())
+}
+
+/// Returns the path to the cached input, downloading it first if it's not cached yet
+pub fn run(options: &FetchOptions) -> Result<PathBuf, String> {
+    let path = cached_input_path(&options.cache_dir, options.year, options.day);
+    if path.exists() {
+        // Inputs never change, so there is no reason to download them again
+        eprintln!("Using the cached input for {} day {}", options.year, options.day);
+        return Ok(path);
+    }
+
+    let token = session_token(options.session_file.as_deref())?;
+    let url = format!(
+        "{}/{}/day/{}/input",
+        options.base_url.trim_end_matches('/'),
+        options.year,
+        options.day
+    );
+    let input = http::get(&url, &token)?;
+
+    let dir = path.parent().expect("cached inputs are stored in a year directory");
+    fs::create_dir_all(dir).map_err(|e| format!("failed to create the cache directory '{}': {}", dir.display(), e))?;
+    // Interrupted downloads shouldn't leave a truncated input in the cache
+    let partial_path = path.with_extension("txt.partial");
+    fs::write(&partial_path, input)
+        .and_then(|_| fs::rename(&partial_path, &path))
+        .map_err(|e| format!("failed to save the input to '{}': {}", path.display(), e))?;
+
+    eprintln!("Downloaded the input for {} day {}", options.year, options.day);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::{fs, thread};
+
+    use crate::cli::FetchOptions;
+    use crate::fetch::{cached_input_path, run};
+
+    /// Starts a fake puzzle server that answers a single request, and returns its URL along with a channel with the
+    /// request lines it received
+    fn serve_once(response: &'static str) -> (String, mpsc::Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+            stream.write_all(response.as_bytes()).unwrap();
+            sender.send(request).unwrap();
+        });
+        (url, receiver)
+    }
+
+    fn options(name: &str, base_url: String) -> (FetchOptions, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let session_file = dir.join("session");
+        fs::write(&session_file, "secret-token\n").unwrap();
+        let options = FetchOptions {
+            day: 1,
+            year: 2024,
+            cache_dir: dir.join("cache"),
+            base_url,
+            session_file: Some(session_file),
+        };
+        (options, dir)
+    }
+
+    #[test]
+    fn downloads_input_once() {
+        let (url, requests) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\n3   4\n4   3\n");
+        let (options, dir) = options("once", url);
+
+        let path = run(&options).unwrap();
+        assert_eq!(path, cached_input_path(&options.cache_dir, 2024, 1));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3   4\n4   3\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request[0], "GET /2024/day/1/input HTTP/1.1");
+        assert!(request.contains(&"Cookie: session=secret-token".to_string()));
+
+        // The fake server is gone, so this only succeeds if the cache is used
+        assert_eq!(run(&options), Ok(path));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_server_errors() {
+        let (url, _requests) =
+            serve_once("HTTP/1.1 400 Bad Request\r\nContent-Length: 28\r\n\r\nPuzzle inputs differ by user");
+        let (options, dir) = options("error", url);
+
+        let error = run(&options).unwrap_err();
+        assert!(error.contains("400 Bad Request"), "{}", error);
+        assert!(!cached_input_path(&options.cache_dir, 2024, 1).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const USER_AGENT: &str = "github.com/h33333333/advent-of-code input fetcher";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Downloads the page using the session cookie.
+///
+/// Plain HTTP is handled directly, while HTTPS requests are delegated to `curl`.
+pub fn get(url: &str, session: &str) -> Result<String, String> {
+    if let Some(url) = url.strip_prefix("http://") {
+        get_plain(url, session)
+    } else if url.starts_with("https://") {
+        get_with_curl(url, session)
+    } else {
+        Err(format!(
+            "unsupported URL '{}', expected an http:// or https:// one",
+            url
+        ))
+    }
+}
+
+/// Sends a minimal HTTP/1.1 request, `url` is the URL without the scheme
+fn get_plain(url: &str, session: &str) -> Result<String, String> {
+    let (host, path) = url.split_at(url.find('/').unwrap_or(url.len()));
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+    let path = if path.is_empty() { "/" } else { path };
+
+    let error = |e: std::io::Error| format!("request to '{}' failed: {}", host, e);
+    let mut stream = TcpStream::connect(&address).map_err(error)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(error)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\nConnection: close\r\n\r\n",
+        path, host, USER_AGENT, session
+    )
+    .map_err(error)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(error)?;
+    parse_response(&response)
+}
+
+fn parse_response(response: &[u8]) -> Result<String, String> {
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or("malformed HTTP response: headers are incomplete")?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| format!("malformed HTTP status line '{}'", status_line))?;
+
+    let mut content_length = None;
+    let mut is_chunked = false;
+    for header in lines {
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<usize>().ok();
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            is_chunked = value.eq_ignore_ascii_case("chunked");
+        }
+    }
+
+    let body = if is_chunked {
+        decode_chunked(body)?
+    } else {
+        match content_length {
+            Some(length) => body
+                .get(..length)
+                .ok_or("HTTP response is shorter than its Content-Length")?
+                .to_vec(),
+            None => body.to_vec(),
+        }
+    };
+
+    if status != 200 {
+        return Err(format!(
+            "server responded with {}: {}",
+            status_line.split_once(' ').map_or(status_line, |(_, status)| status),
+            String::from_utf8_lossy(&body).trim()
+        ));
+    }
+    String::from_utf8(body).map_err(|_| "downloaded input is not valid UTF-8".to_string())
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let malformed = || "malformed chunked HTTP response".to_string();
+    let mut decoded = Vec::new();
+    loop {
+        let size_end = body
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(malformed)?;
+        let size = std::str::from_utf8(&body[..size_end]).map_err(|_| malformed())?;
+        // Chunk extensions are separated from the size by a semicolon
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| malformed())?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        // The size comes from the server, so it can be arbitrarily large
+        let chunk_start = size_end + 2;
+        let chunk_end = chunk_start.checked_add(size).ok_or_else(malformed)?;
+        let chunk = body.get(chunk_start..chunk_end).ok_or_else(malformed)?;
+        decoded.extend_from_slice(chunk);
+        body = body.get(chunk_end + 2..).ok_or_else(malformed)?;
+    }
+}
+
+fn get_with_curl(url: &str, session: &str) -> Result<String, String> {
+    // The cookie is passed through stdin, so that the token doesn't show up in the process list
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--user-agent", USER_AGENT, "--header", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run curl, which is required for https:// URLs: {}", e))?;
+
+    let mut stdin = curl.stdin.take().expect("stdin is piped");
+    writeln!(stdin, "Cookie: session={}", session).map_err(|e| format!("failed to pass the cookie to curl: {}", e))?;
+    drop(stdin);
+
+    let output = curl
+        .wait_with_output()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "request to '{}' failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|_| "downloaded input is not valid UTF-8".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fetch::http::parse_response;
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n1 2\nextra"),
+            Ok("1 2\n".to_string())
+        );
+        assert_eq!(
+            parse_response(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1 2\n\r\n3;ext=1\r\n3 4\r\n0\r\n\r\n"
+            ),
+            Ok("1 2\n3 4".to_string())
+        );
+        assert_eq!(
+            parse_response(b"HTTP/1.1 404 Not Found\r\n\r\nPlease don't repeatedly request this endpoint"),
+            Err("server responded with 404 Not Found: Please don't repeatedly request this endpoint".to_string())
+        );
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort").is_err());
+        assert_eq!(
+            parse_response(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\n1 2\n\r\n0\r\n\r\n"
+            ),
+            Err("malformed chunked HTTP response".to_string())
+        );
+        assert!(parse_response(b"garbage").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+mod bench;
+mod cli;
+mod days;
+mod fetch;
+mod scaffold;
+mod verify;
+
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+use cli::{Command, DaySelection, USAGE};
+use common::{input_name, input_path, read_input, Format};
+use days::{find_day, Day, DAYS};
+
+/// Solves the day for a single input, `print_name` adds the input's name to the text output
+fn run_day(day: &Day, input_path: &Path, part: Option<u8>, format: Format, print_name: bool) -> Result<(), String> {
+    let name = input_name(input_path);
+    let input =
+        read_input(input_path).map_err(|e| format!("error: failed to read the input file '{}': {}", name, e))?;
+
+    let report = (day.solve)(&input, part).map_err(|e| e.render(&name, &input))?;
+
+    match format {
+        Format::Text => {
+            if print_name {
+                println!("Day {}, {} ({:.3?})", day.number, name, report.total_time());
+            } else {
+                println!("Day {} ({:.3?})", day.number, report.total_time());
+            }
+            for (idx, answer) in report.answers.iter().enumerate() {
+                if let Some(answer) = answer {
+                    println!("    Part {}: {}", idx + 1, answer);
+                }
+            }
+        }
+        Format::Json => println!("{}", report.to_json(&name)),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match command {
+        Command::Bench(options) => match bench::run(&options) {
+            Ok(true) => {}
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Fetch(options) => match fetch::run(&options) {
+            // Only the path goes to stdout, so that it can be passed straight to the solvers
+            Ok(path) => println!("{}", path.display()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::NewDay(options) => match scaffold::run(&options) {
+            Ok(crate_dir) => println!("Created '{}' and registered it in the runner", crate_dir.display()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Verify(options) => match verify::run(&options) {
+            Ok(true) => {}
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Run {
+            selection: DaySelection::Single { day, mut inputs },
+            part,
+            inputs_dir,
+            format,
+        } => {
+            let Some(day) = find_day(day) else {
+                eprintln!("error: day {} is not solved yet", day);
+                return ExitCode::FAILURE;
+            };
+            if inputs.is_empty() {
+                inputs.push(input_path(&inputs_dir, day.number));
+            }
+            let print_names = inputs.len() > 1;
+
+            // Every input is solved even if some of them fail
+            let mut failed = false;
+            for input_path in inputs.iter() {
+                if let Err(e) = run_day(day, input_path, part, format, print_names) {
+                    eprintln!("{}", e);
+                    failed = true;
+                }
+            }
+            if failed {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Run {
+            selection: DaySelection::All,
+            part,
+            inputs_dir,
+            format,
+        } => {
+            let mut failed = false;
+            for day in DAYS.iter() {
+                let input_path = input_path(&inputs_dir, day.number);
+                if !input_path.exists() {
+                    // Skipped days would break the JSON output with one object per line
+                    if format == Format::Json {
+                        continue;
+                    }
+                    println!("Day {} (skipped, no input at '{}')", day.number, input_path.display());
+                    continue;
+                }
+                if let Err(e) = run_day(day, &input_path, part, format, false) {
+                    eprintln!("{}", e);
+                    failed = true;
+                }
+            }
+            if failed {
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::NewDayOptions;
+
+const CARGO_TOML_TEMPLATE: &str = r#"[package]
+name = "day-{day}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+common = { path = "../common" }
+"#;
+
+const LIB_TEMPLATE: &str = r#"use common::{InputError, Solution};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+
+    type Input = String;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(_input: &Self::Input) -> Self::Answer1 {
+        todo!("solve part 1")
+    }
+
+    fn part_2(_input: &Self::Input) -> Self::Answer2 {
+        todo!("solve part 2")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Solution;
+
+    use crate::Day{day};
+
+    const SAMPLE_INPUT: &str = "TODO: sample input from the puzzle description";
+    const SAMPLE_PART_1: u64 = 0;
+    const SAMPLE_PART_2: u64 = 0;
+
+    #[test]
+    #[ignore = "the sample isn't filled in yet"]
+    fn correctly_solves_both_parts() {
+        let input = Day{day}::parse(SAMPLE_INPUT).unwrap();
+
+        assert_eq!(Day{day}::part_1(&input), SAMPLE_PART_1);
+        assert_eq!(Day{day}::part_2(&input), SAMPLE_PART_2);
+    }
+}
+"#;
+
+const MAIN_TEMPLATE: &str = r#"use std::process::ExitCode;
+
+use day_{day}::Day{day};
+
+fn main() -> ExitCode {
+    common::run::<Day{day}>(["part 1: {}", "part 2: {}"])
+}
+"#;
+
+/// Finds the workspace root by walking up from the given directory
+fn find_workspace_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| {
+            dir.join("crates").join("aoc").is_dir()
+                && fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|manifest| manifest.contains("[workspace]"))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Adds the day crate to the dependencies of the runner, keeping the days ordered
+fn register_dependency(manifest: &str, day: u8) -> Result<String, String> {
+    let dependency_day = |line: &str| line.strip_prefix("day-")?.split_once(' ')?.0.parse::<u8>().ok();
+    let lines = manifest.lines().collect::<Vec<_>>();
+    if lines.iter().any(|line| dependency_day(line) == Some(day)) {
+        return Err(format!("day {} is already a dependency of the runner", day));
+    }
+
+    let insert_at = lines
+        .iter()
+        .position(|line| dependency_day(line).is_some_and(|existing| existing > day))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| dependency_day(line).is_some())
+                .map(|idx| idx + 1)
+        })
+        .ok_or("runner's Cargo.toml has no day dependencies")?;
+    let dependency = format!("day-{day} = {{ path = \"../day-{day}\" }}");
+
+    let mut lines = lines;
+    lines.insert(insert_at, &dependency);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the `DAYS` table of the runner, keeping the days ordered
+fn register_day(days_source: &str, day: u8) -> Result<String, String> {
+    const TABLE_START: &str = "pub const DAYS: [Day; ";
+    let malformed = || "runner's DAYS table has an unexpected format".to_string();
+
+    let table_start = days_source.find(TABLE_START).ok_or_else(malformed)? + TABLE_START.len();
+    let count_len = days_source[table_start..].find(']').ok_or_else(malformed)?;
+    let count = days_source[table_start..table_start + count_len]
+        .parse::<usize>()
+        .map_err(|_| malformed())?;
+    let table_end = table_start + days_source[table_start..].find("\n];").ok_or_else(malformed)?;
+
+    let entry_day = |line: &str| {
+        line.trim()
+            .strip_prefix("day::<day_")?
+            .split_once(':')?
+            .0
+            .parse::<u8>()
+            .ok()
+    };
+    let mut entries = days_source[table_start..table_end]
+        .lines()
+        .skip(1)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if entries.iter().any(|entry| entry_day(entry) == Some(day)) {
+        return Err(format!("day {} is already registered in the runner", day));
+    }
+    let insert_at = entries
+        .iter()
+        .position(|entry| entry_day(entry).is_some_and(|existing| existing > day))
+        .unwrap_or(entries.len());
+    entries.insert(insert_at, format!("    day::<day_{day}::Day{day}>(),"));
+
+    Ok(format!(
+        "{}{}] = [\n{}{}",
+        &days_source[..table_start],
+        count + 1,
+        entries.join("\n"),
+        &days_source[table_end..]
+    ))
+}
+
+/// Generates a crate for the new day and registers it in the runner. Returns the path to the new crate.
+pub fn run(options: &NewDayOptions) -> Result<PathBuf, String> {
+    let root = match &options.root {
+        Some(root) => root.clone(),
+        None => {
+            let current_dir =
+                std::env::current_dir().map_err(|e| format!("failed to get the current directory: {}", e))?;
+            find_workspace_root(&current_dir).ok_or("failed to find the workspace root, use --root")?
+        }
+    };
+    let crate_dir = root.join("crates").join(format!("day-{}", options.day));
+    if crate_dir.exists() {
+        return Err(format!(
+            "'{}' already exists, refusing to overwrite it",
+            crate_dir.display()
+        ));
+    }
+
+    let read =
+        |path: &Path| fs::read_to_string(path).map_err(|e| format!("failed to read '{}': {}", path.display(), e));
+    let write = |path: &Path, contents: &str| {
+        fs::write(path, contents).map_err(|e| format!("failed to write '{}': {}", path.display(), e))
+    };
+
+    // Both runner files are updated in memory first, so that nothing is written if the runner can't be updated
+    let runner_dir = root.join("crates").join("aoc");
+    let manifest_path = runner_dir.join("Cargo.toml");
+    let days_path = runner_dir.join("src").join("days.rs");
+    let manifest = register_dependency(&read(&manifest_path)?, options.day)?;
+    let days_source = register_day(&read(&days_path)?, options.day)?;
+
+    let src_dir = crate_dir.join("src");
+    fs::create_dir_all(&src_dir).map_err(|e| format!("failed to create '{}': {}", src_dir.display(), e))?;
+    let render = |template: &str| template.replace("{day}", &options.day.to_string());
+    write(&crate_dir.join("Cargo.toml"), &render(CARGO_TOML_TEMPLATE))?;
+    write(&src_dir.join("lib.rs"), &render(LIB_TEMPLATE))?;
+    write(&src_dir.join("main.rs"), &render(MAIN_TEMPLATE))?;
+
+    write(&manifest_path, &manifest)?;
+    write(&days_path, &days_source)?;
+
+    Ok(crate_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::cli::NewDayOptions;
+    use crate::scaffold::{register_day, register_dependency, run};
+
+    const DAYS_SOURCE: &str = "pub const DAYS: [Day; 2] = [\n    day::<day_1::Day1>(),\n    day::<day_3::Day3>(),\n];\n\npub fn find_day() {}\n";
+
+    const MANIFEST: &str =
+        "[dependencies]\ncommon = { path = \"../common\" }\nday-1 = { path = \"../day-1\" }\nday-3 = { path = \"../day-3\" }\n";
+
+    #[test]
+    fn registers_days_in_order() {
+        assert_eq!(
+            register_day(DAYS_SOURCE, 2),
+            Ok("pub const DAYS: [Day; 3] = [\n    day::<day_1::Day1>(),\n    day::<day_2::Day2>(),\n    day::<day_3::Day3>(),\n];\n\npub fn find_day() {}\n".to_string())
+        );
+        assert_eq!(
+            register_day(DAYS_SOURCE, 13),
+            Ok("pub const DAYS: [Day; 3] = [\n    day::<day_1::Day1>(),\n    day::<day_3::Day3>(),\n    day::<day_13::Day13>(),\n];\n\npub fn find_day() {}\n".to_string())
+        );
+        assert!(register_day(DAYS_SOURCE, 3).is_err());
+
+        assert_eq!(
+            register_dependency(MANIFEST, 13),
+            Ok(MANIFEST.to_string() + "day-13 = { path = \"../day-13\" }\n")
+        );
+        assert!(register_dependency(MANIFEST, 1).is_err());
+    }
+
+    #[test]
+    fn generates_new_day_without_overwriting() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let runner_dir = root.join("crates").join("aoc");
+        fs::create_dir_all(runner_dir.join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        fs::write(runner_dir.join("Cargo.toml"), MANIFEST).unwrap();
+        fs::write(runner_dir.join("src").join("days.rs"), DAYS_SOURCE).unwrap();
+        let options = NewDayOptions {
+            day: 2,
+            root: Some(root.clone()),
+        };
+
+        let crate_dir = run(&options).unwrap();
+        let lib = fs::read_to_string(crate_dir.join("src").join("lib.rs")).unwrap();
+        assert!(lib.contains("impl Solution for Day2 {\n    const DAY: u8 = 2;"));
+        let main = fs::read_to_string(crate_dir.join("src").join("main.rs")).unwrap();
+        assert!(main.contains("common::run::<Day2>([\"part 1: {}\", \"part 2: {}\"])"));
+        let days_source = fs::read_to_string(runner_dir.join("src").join("days.rs")).unwrap();
+        assert!(days_source.contains("day::<day_2::Day2>()"));
+
+        // Running it again must leave the existing crate untouched
+        fs::write(crate_dir.join("src").join("lib.rs"), "solved").unwrap();
+        assert!(run(&options).is_err());
+        assert_eq!(
+            fs::read_to_string(crate_dir.join("src").join("lib.rs")).unwrap(),
+            "solved"
+        );
+        assert_eq!(
+            fs::read_to_string(runner_dir.join("src").join("days.rs")).unwrap(),
+            days_source
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
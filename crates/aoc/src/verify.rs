@@ -0,0 +1,264 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use common::{input_path, Json};
+
+use crate::cli::VerifyOptions;
+use crate::days::{find_day, DAYS};
+
+/// Expected answers for a single input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedAnswers {
+    pub day: u8,
+    /// Path to the input file, relative to the answers file
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+impl RecordedAnswers {
+    fn to_json(&self) -> Json {
+        let mut entries = vec![
+            ("day".to_string(), Json::from(self.day as u64)),
+            ("input".to_string(), Json::from(self.input.as_str())),
+        ];
+        for (idx, answer) in self.answers.iter().enumerate() {
+            if let Some(answer) = answer {
+                entries.push((format!("part{}", idx + 1), Json::from(answer.as_str())));
+            }
+        }
+        Json::Object(entries)
+    }
+
+    fn from_json(value: &Json) -> Option<Self> {
+        // Answers can be recorded either as strings or as numbers
+        let answer = |key: &str| match value.get(key)? {
+            Json::String(answer) => Some(answer.clone()),
+            Json::Number(answer) => Some(answer.to_string()),
+            _ => None,
+        };
+        Some(RecordedAnswers {
+            day: value.get("day")?.as_u64()? as u8,
+            input: value.get("input")?.as_str()?.to_string(),
+            answers: [answer("part1"), answer("part2")],
+        })
+    }
+}
+
+pub fn load_answers(path: &Path) -> Result<Vec<RecordedAnswers>, String> {
+    let path_str = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read the answers '{}': {}", path_str, e))?;
+    let answers = Json::parse(&text).map_err(|e| e.render(&path_str, &text))?;
+
+    answers
+        .get("answers")
+        .and_then(Json::as_array)
+        .ok_or_else(|| format!("answers file '{}' has no 'answers' list", path_str))?
+        .iter()
+        .map(|entry| {
+            RecordedAnswers::from_json(entry)
+                .ok_or_else(|| format!("answers file '{}' has a malformed entry: {}", path_str, entry))
+        })
+        .collect()
+}
+
+pub fn save_answers(path: &Path, answers: &[RecordedAnswers]) -> Result<(), String> {
+    let answers = Json::object([(
+        "answers",
+        Json::Array(answers.iter().map(RecordedAnswers::to_json).collect()),
+    )]);
+    fs::write(path, answers.pretty() + "\n")
+        .map_err(|e| format!("failed to save the answers to '{}': {}", path.display(), e))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// No answer was recorded for the part
+    Missing,
+}
+
+pub fn check(expected: Option<&str>, actual: &str) -> Status {
+    match expected {
+        Some(expected) if expected == actual => Status::Pass,
+        Some(_) => Status::Fail,
+        None => Status::Missing,
+    }
+}
+
+/// Runs every solver against every recorded input, as well as against inputs from the inputs directory that have no
+/// recorded answers yet. Returns `false` if any of the answers didn't match.
+pub fn run(options: &VerifyOptions) -> Result<bool, String> {
+    let mut recorded = if options.answers.exists() {
+        load_answers(&options.answers)?
+    } else if options.record {
+        Vec::new()
+    } else {
+        return Err(format!(
+            "answers file '{}' doesn't exist, use --record to create it",
+            options.answers.display()
+        ));
+    };
+    let base_dir = normalize(options.answers.parent().unwrap_or(Path::new("")));
+
+    // Inputs from the inputs directory are checked as well, so that missing answers are reported
+    for day in DAYS.iter() {
+        let path = normalize(&input_path(&options.inputs_dir, day.number));
+        if path.exists()
+            && !recorded
+                .iter()
+                .any(|entry| normalize(&base_dir.join(&entry.input)) == path)
+        {
+            recorded.push(RecordedAnswers {
+                day: day.number,
+                input: relative_path(&path, &base_dir),
+                answers: [None, None],
+            });
+        }
+    }
+    recorded.sort_by(|a, b| (a.day, &a.input).cmp(&(b.day, &b.input)));
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for entry in recorded.iter_mut() {
+        let path = base_dir.join(&entry.input);
+        let actual = match solve(entry.day, &path) {
+            Ok(actual) => actual,
+            Err(e) => {
+                println!("FAIL     day {:>2}         {}", entry.day, entry.input);
+                eprintln!("{}", e);
+                failed += entry.answers.len();
+                continue;
+            }
+        };
+
+        for (idx, (expected, actual)) in entry.answers.iter_mut().zip(actual).enumerate() {
+            let status = check(expected.as_deref(), &actual);
+            let label = format!("day {:>2} part {}  {}", entry.day, idx + 1, entry.input);
+            match status {
+                Status::Pass => {
+                    passed += 1;
+                    println!("PASS     {}", label);
+                }
+                Status::Fail => {
+                    failed += 1;
+                    println!(
+                        "FAIL     {}  expected {}, got {}",
+                        label,
+                        expected.as_deref().unwrap_or_default(),
+                        actual
+                    );
+                }
+                Status::Missing => {
+                    missing += 1;
+                    println!("MISSING  {}  got {}", label, actual);
+                    if options.record {
+                        *expected = Some(actual);
+                    }
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+
+    if options.record && missing > 0 {
+        recorded.retain(|entry| entry.answers.iter().any(Option::is_some));
+        save_answers(&options.answers, &recorded)?;
+        println!(
+            "Recorded {} missing answer(s) to '{}'",
+            missing,
+            options.answers.display()
+        );
+    }
+
+    Ok(failed == 0)
+}
+
+fn solve(day: u8, path: &Path) -> Result<[String; 2], String> {
+    let day = find_day(day).ok_or_else(|| format!("error: day {} is not solved yet", day))?;
+    let input = fs::read_to_string(path)
+        .map_err(|e| format!("error: failed to read the input file '{}': {}", path.display(), e))?;
+    let report = (day.solve)(&input, None).map_err(|e| e.render(&path.display().to_string(), &input))?;
+
+    Ok(report.answers.map(|answer| answer.expect("both parts are solved").text))
+}
+
+/// Drops the `.` components, so that the same relative paths compare equal however they are spelled
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+fn relative_path(path: &Path, base_dir: &Path) -> String {
+    path.strip_prefix(base_dir)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::cli::VerifyOptions;
+    use crate::verify::{check, load_answers, normalize, run, RecordedAnswers, Status};
+
+    #[test]
+    fn compares_answers() {
+        assert_eq!(check(Some("11"), "11"), Status::Pass);
+        assert_eq!(check(Some("11"), "12"), Status::Fail);
+        assert_eq!(check(None, "11"), Status::Missing);
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(
+            normalize(Path::new("./inputs/./day-01.txt")),
+            Path::new("inputs/day-01.txt")
+        );
+        assert_eq!(normalize(Path::new("/aoc/./inputs")), Path::new("/aoc/inputs"));
+        assert_eq!(normalize(Path::new(".")), Path::new(""));
+    }
+
+    #[test]
+    fn records_and_verifies_answers() {
+        let dir = std::env::temp_dir().join(format!("aoc-verify-{}", std::process::id()));
+        let inputs_dir = dir.join("inputs");
+        fs::create_dir_all(&inputs_dir).unwrap();
+        fs::write(
+            inputs_dir.join("day-01.txt"),
+            "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n",
+        )
+        .unwrap();
+        let mut options = VerifyOptions {
+            answers: dir.join("answers.json"),
+            inputs_dir,
+            record: true,
+        };
+
+        assert_eq!(run(&options), Ok(true));
+        assert_eq!(
+            load_answers(&options.answers),
+            Ok(vec![RecordedAnswers {
+                day: 1,
+                input: "inputs/day-01.txt".to_string(),
+                answers: [Some("11".to_string()), Some("31".to_string())],
+            }])
+        );
+
+        options.record = false;
+        assert_eq!(run(&options), Ok(true));
+
+        fs::write(
+            &options.answers,
+            r#"{"answers": [{"day": 1, "input": "inputs/day-01.txt", "part1": 11, "part2": "30"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(run(&options), Ok(false));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
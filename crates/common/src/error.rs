@@ -0,0 +1,192 @@
+use std::fmt::{self, Write as _};
+use std::io;
+use std::str::FromStr;
+
+/// An error that points to the exact place in the input that failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, counted in characters
+    pub column: usize,
+    /// The offending text, empty if the input ended unexpectedly
+    pub text: String,
+    /// Description of what was expected instead
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: impl Into<String>, expected: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Creates an error for a token of a line.
+    ///
+    /// `token` must be a subslice of `line`, as its column is derived from its position within the line.
+    pub fn at_token(line_idx: usize, line: &str, token: &str, expected: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(line.as_ptr() as usize)
+            .filter(|offset| offset + token.len() <= line.len())
+            .expect("token must be a part of the line");
+
+        ParseError::new(line_idx + 1, line[..offset].chars().count() + 1, token, expected)
+    }
+
+    /// Creates an error for a line that ended before all the expected items were found
+    pub fn at_end_of_line(line_idx: usize, line: &str, expected: impl Into<String>) -> Self {
+        ParseError::new(line_idx + 1, line.chars().count() + 1, "", expected)
+    }
+
+    /// Renders a caret-style diagnostic that shows the offending line of the source
+    pub fn render(&self, path: &str, source: &str) -> String {
+        let line = source.split('\n').nth(self.line - 1).unwrap_or_default();
+        let line_number = self.line.to_string();
+        let padding = " ".repeat(line_number.len());
+
+        // Control characters (e.g. a stray '\r') are escaped, so the caret has to be shifted accordingly
+        let mut rendered_line = String::new();
+        let mut caret_offset = 0;
+        let mut caret_len = 0;
+        for (idx, character) in line.chars().enumerate() {
+            let rendered = escape(character);
+            if idx + 1 < self.column {
+                caret_offset += rendered.chars().count();
+            } else if idx + 1 < self.column + self.text.chars().count() {
+                caret_len += rendered.chars().count();
+            }
+            rendered_line.push_str(&rendered);
+        }
+        if caret_offset < self.column - 1 {
+            // The error points past the end of the line
+            caret_offset = self.column - 1;
+        }
+
+        let mut diagnostic = format!("error: {}\n", self);
+        let _ = writeln!(diagnostic, "{}--> {}:{}:{}", padding, path, self.line, self.column);
+        let _ = writeln!(diagnostic, "{} |", padding);
+        let _ = writeln!(diagnostic, "{} | {}", line_number, rendered_line);
+        let _ = write!(
+            diagnostic,
+            "{} | {}{}",
+            padding,
+            " ".repeat(caret_offset),
+            "^".repeat(caret_len.max(1))
+        );
+        diagnostic
+    }
+}
+
+fn escape(character: char) -> String {
+    if character.is_control() {
+        character.escape_default().to_string()
+    } else {
+        character.to_string()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "expected {}, found nothing", self.expected)
+        } else {
+            write!(f, "expected {}, found {:?}", self.expected, self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a token of a line, reporting its position if it's not valid
+pub fn parse_token<T: FromStr>(line_idx: usize, line: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at_token(line_idx, line, token, expected))
+}
+
+/// An error that happened while reading or parsing the input
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl InputError {
+    /// Renders the error, including a caret-style diagnostic for parse errors
+    pub fn render(&self, path: &str, source: &str) -> String {
+        match self {
+            InputError::Io(e) => format!("error: failed to read '{}': {}", path, e),
+            InputError::Parse(e) => e.render(path, source),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "failed to read the input: {}", e),
+            InputError::Parse(e) => write!(f, "line {}, column {}: {}", e.line, e.column, e),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+impl From<ParseError> for InputError {
+    fn from(e: ParseError) -> Self {
+        InputError::Parse(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{parse_token, ParseError};
+
+    #[test]
+    fn finds_column_of_token() {
+        let line = "12 ab 34";
+        let error = parse_token::<u32>(1, line, &line[3..5], "an integer").unwrap_err();
+
+        assert_eq!(error, ParseError::new(2, 4, "ab", "an integer"));
+    }
+
+    #[test]
+    fn renders_caret_diagnostic() {
+        let source = "1   2\n3   4\r\n";
+        let line = "3   4\r";
+        let error = parse_token::<u32>(1, line, &line[4..], "an integer").unwrap_err();
+
+        assert_eq!(
+            error.render("input.txt", source),
+            "error: expected an integer, found \"4\\r\"\n \
+             --> input.txt:2:5\n  \
+             |\n\
+             2 | 3   4\\r\n  \
+             |     ^^^"
+        );
+    }
+
+    #[test]
+    fn renders_caret_past_end_of_line() {
+        let error = ParseError::at_end_of_line(0, "1", "second list item");
+
+        assert_eq!(
+            error.render("input.txt", "1"),
+            "error: expected second list item, found nothing\n \
+             --> input.txt:1:2\n  \
+             |\n\
+             1 | 1\n  \
+             |  ^"
+        );
+    }
+}
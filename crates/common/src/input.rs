@@ -0,0 +1,61 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// Input path that stands for the standard input
+pub const STDIN: &str = "-";
+
+/// Environment variable that overrides the default directory with the inputs
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Directory with the inputs, `$AOC_INPUT_DIR` or `inputs` if the variable isn't set
+pub fn default_inputs_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| PathBuf::from("inputs"), PathBuf::from)
+}
+
+/// Returns the conventional path of the input file for the given day
+pub fn input_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    inputs_dir.join(format!("day-{:02}.txt", day))
+}
+
+/// Reads the input file, or the standard input if the path is `-`
+pub fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new(STDIN) {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Name of the input to be used in messages and diagnostics
+pub fn input_name(path: &Path) -> String {
+    if path == Path::new(STDIN) {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::input::{input_name, input_path, read_input};
+
+    #[test]
+    fn follows_input_conventions() {
+        assert_eq!(input_path(Path::new("data"), 7), PathBuf::from("data/day-07.txt"));
+        assert_eq!(input_name(Path::new("-")), "<stdin>");
+        assert_eq!(input_name(Path::new("data/day-07.txt")), "data/day-07.txt");
+
+        let path = std::env::temp_dir().join(format!("aoc-input-{}.txt", std::process::id()));
+        fs::write(&path, "1 2 3\n").unwrap();
+        assert_eq!(read_input(&path).unwrap(), "1 2 3\n");
+        fs::remove_file(path).unwrap();
+    }
+}
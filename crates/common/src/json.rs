@@ -0,0 +1,470 @@
+use std::fmt;
+
+use crate::ParseError;
+
+/// A minimal JSON value, objects keep the order of their keys
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Creates an object from a list of key-value pairs
+    pub fn object<K: Into<String>, I: IntoIterator<Item = (K, Json)>>(entries: I) -> Json {
+        Json::Object(entries.into_iter().map(|(key, value)| (key.into(), value)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_f64()
+            .filter(|number| number.fract() == 0.0 && *number >= 0.0)
+            .map(|number| number as u64)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Formats the value with every array item and object entry on its own line
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output, Some(0))
+            .expect("writing to a string can't fail");
+        output
+    }
+
+    pub fn parse(text: &str) -> Result<Json, ParseError> {
+        let mut parser = Parser { text, position: 0 };
+        parser.skip_whitespace();
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.position != text.len() {
+            return Err(parser.error("end of input"));
+        }
+        Ok(value)
+    }
+
+    fn write(&self, f: &mut impl fmt::Write, indent: Option<usize>) -> fmt::Result {
+        let separator = |f: &mut dyn fmt::Write, level: usize| match indent {
+            Some(_) => write!(f, "\n{}", "  ".repeat(level)),
+            None => Ok(()),
+        };
+        let nested = indent.map(|level| level + 1);
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(number) if number.is_finite() => write!(f, "{}", number),
+            // JSON has no representation for NaN and infinity
+            Json::Number(_) => f.write_str("null"),
+            Json::String(string) => write_string(f, string),
+            Json::Array(items) if items.is_empty() => f.write_str("[]"),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_char(',')?;
+                    }
+                    separator(f, nested.unwrap_or_default())?;
+                    item.write(f, nested)?;
+                }
+                separator(f, indent.unwrap_or_default())?;
+                f.write_char(']')
+            }
+            Json::Object(entries) if entries.is_empty() => f.write_str("{}"),
+            Json::Object(entries) => {
+                f.write_char('{')?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_char(',')?;
+                    }
+                    separator(f, nested.unwrap_or_default())?;
+                    write_string(f, key)?;
+                    f.write_str(if indent.is_some() { ": " } else { ":" })?;
+                    value.write(f, nested)?;
+                }
+                separator(f, indent.unwrap_or_default())?;
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut impl fmt::Write, string: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for character in string.chars() {
+        match character {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            character if character.is_control() => write!(f, "\\u{:04x}", character as u32)?,
+            character => f.write_char(character)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, None)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+/// Largest integer that an `f64` (and thus most JSON parsers) can represent exactly
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Conversion of puzzle answers into JSON.
+///
+/// Integers that can't be represented exactly by JSON numbers are emitted as strings.
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+macro_rules! impl_to_json_for_small_int {
+    ($($int:ty),*) => {
+        $(impl ToJson for $int {
+            fn to_json(&self) -> Json {
+                Json::Number(f64::from(*self))
+            }
+        })*
+    };
+}
+
+macro_rules! impl_to_json_for_wide_int {
+    ($($int:ty),*) => {
+        $(impl ToJson for $int {
+            fn to_json(&self) -> Json {
+                if self.unsigned_abs() as u64 <= MAX_SAFE_INTEGER {
+                    Json::Number(*self as f64)
+                } else {
+                    Json::String(self.to_string())
+                }
+            }
+        })*
+    };
+}
+
+macro_rules! impl_to_json_for_huge_int {
+    ($($int:ty),*) => {
+        // Kept as strings regardless of the value, so that the type of the answer doesn't depend on the input
+        $(impl ToJson for $int {
+            fn to_json(&self) -> Json {
+                Json::String(self.to_string())
+            }
+        })*
+    };
+}
+
+impl_to_json_for_small_int!(i8, i16, i32, u8, u16, u32);
+impl_to_json_for_wide_int!(i64, isize);
+impl_to_json_for_huge_int!(i128, u128);
+
+impl ToJson for u64 {
+    fn to_json(&self) -> Json {
+        if *self <= MAX_SAFE_INTEGER {
+            Json::Number(*self as f64)
+        } else {
+            Json::String(self.to_string())
+        }
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Json {
+        (*self as u64).to_json()
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    /// Byte offset of the next character
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|character| character.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    /// Creates an error pointing to the character at the current position
+    fn error(&self, expected: &str) -> ParseError {
+        let consumed = &self.text[..self.position];
+        let line_idx = consumed.matches('\n').count();
+        let line_start = consumed.rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = self.text[line_start..]
+            .find('\n')
+            .map_or(self.text.len(), |idx| line_start + idx);
+        let line = &self.text[line_start..line_end];
+        match self.peek().filter(|character| *character != '\n') {
+            Some(character) => ParseError::at_token(
+                line_idx,
+                line,
+                &line[self.position - line_start..self.position - line_start + character.len_utf8()],
+                expected,
+            ),
+            None => ParseError::at_end_of_line(line_idx, line, expected),
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ParseError> {
+        if self.text[self.position..].starts_with(expected) {
+            self.position += expected.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", expected)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, ParseError> {
+        match self.peek() {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.parse_string().map(Json::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_object(),
+            Some(character) if character == '-' || character.is_ascii_digit() => self.parse_number(),
+            _ => Err(self.error("a JSON value")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json, ParseError> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|character| matches!(character, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+        {
+            self.position += 1;
+        }
+        match self.text[start..self.position].parse() {
+            Ok(number) => Ok(Json::Number(number)),
+            Err(_) => {
+                self.position = start;
+                Err(self.error("a number"))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let code = self
+                                .text
+                                .get(self.position + 1..self.position + 5)
+                                .and_then(|code| u32::from_str_radix(code, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("a unicode escape sequence"))?;
+                            self.position += 4;
+                            code
+                        }
+                        _ => return Err(self.error("an escape sequence")),
+                    };
+                    self.position += 1;
+                    string.push(escaped);
+                }
+                Some(character) if !character.is_control() => {
+                    self.position += character.len_utf8();
+                    string.push(character);
+                }
+                _ => return Err(self.error("'\"'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, ParseError> {
+        self.expect("[")?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, ParseError> {
+        self.expect("{")?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            self.skip_whitespace();
+            entries.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return Err(self.error("',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::{Json, ToJson};
+    use crate::ParseError;
+
+    #[test]
+    fn round_trips_values() {
+        let value = Json::object([
+            ("day", Json::from(10u64)),
+            ("name", Json::from("quote \" and\nnewline")),
+            (
+                "items",
+                Json::Array(vec![Json::Null, Json::Bool(true), Json::from(-1.5)]),
+            ),
+            ("empty", Json::Object(Vec::new())),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"day":10,"name":"quote \" and\nnewline","items":[null,true,-1.5],"empty":{}}"#
+        );
+        assert_eq!(Json::parse(&value.to_string()), Ok(value.clone()));
+        assert_eq!(Json::parse(&value.pretty()), Ok(value));
+    }
+
+    #[test]
+    fn formats_pretty_output() {
+        let value = Json::object([("a", Json::Array(vec![Json::from(1u64), Json::from(2u64)]))]);
+
+        assert_eq!(value.pretty(), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+    }
+
+    #[test]
+    fn keeps_big_integers_as_strings() {
+        assert_eq!(42u32.to_json(), Json::Number(42.0));
+        assert_eq!((-7i64).to_json(), Json::Number(-7.0));
+        assert_eq!(
+            9_007_199_254_740_991u64.to_json(),
+            Json::Number(9_007_199_254_740_991.0)
+        );
+        assert_eq!(u64::MAX.to_json(), Json::from("18446744073709551615"));
+        assert_eq!(i64::MIN.to_json(), Json::from("-9223372036854775808"));
+        assert_eq!(55312u128.to_json(), Json::from("55312"));
+    }
+
+    #[test]
+    fn reports_position_of_invalid_json() {
+        assert_eq!(
+            Json::parse("{\n  \"a\": 1,\n  \"b\" 2\n}"),
+            Err(ParseError::new(3, 7, "2", "':'"))
+        );
+        assert_eq!(Json::parse("[1, 2"), Err(ParseError::new(1, 6, "", "',' or ']'")));
+    }
+}
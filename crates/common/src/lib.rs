@@ -0,0 +1,200 @@
+mod error;
+mod input;
+mod json;
+mod report;
+mod rng;
+
+use std::env;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+pub use error::{parse_token, InputError, ParseError};
+pub use input::{default_inputs_dir, input_name, input_path, read_input, INPUT_DIR_VAR, STDIN};
+pub use json::{Json, ToJson};
+pub use report::{Answer, Format, Report};
+pub use rng::Rng;
+
+/// A solution for a single day of the puzzle.
+///
+/// The input is parsed only once and then shared between both parts.
+pub trait Solution {
+    /// Day of the puzzle this solution is for
+    const DAY: u8;
+
+    /// Whether the parts are only found together by [`Solution::solve`], so they can't be timed separately
+    const SHARED_SOLVER: bool = false;
+
+    type Input;
+    type Answer1: Display + ToJson;
+    type Answer2: Display + ToJson;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError>;
+
+    fn part_1(input: &Self::Input) -> Self::Answer1;
+
+    fn part_2(input: &Self::Input) -> Self::Answer2;
+
+    /// Solves both parts at once.
+    ///
+    /// Should be overriden by solutions that find both answers in a single pass.
+    fn solve(input: &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        (Self::part_1(input), Self::part_2(input))
+    }
+}
+
+/// Parses the arguments of a day binary: `[--format <text|json>] [INPUT]...`
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<(Format, Vec<PathBuf>), String> {
+    let mut format = Format::Text;
+    let mut input_paths = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = args
+                    .next()
+                    .ok_or("--format requires either 'text' or 'json'")?
+                    .parse()?
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            path => input_paths.push(PathBuf::from(path)),
+        }
+    }
+
+    Ok((format, input_paths))
+}
+
+/// Entry point shared by the day binaries.
+///
+/// Every provided input is solved separately, `-` stands for the standard input. If no inputs are provided, the
+/// day's input is taken from the default inputs directory. In the text format every answer is printed using its
+/// template, where `{}` is replaced with the answer.
+pub fn run<S: Solution>(templates: [&str; 2]) -> ExitCode {
+    let (format, mut input_paths) = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!(
+                "error: {}\n\nUsage: day-{} [--format <text|json>] [INPUT]...",
+                e,
+                S::DAY
+            );
+            return ExitCode::from(2);
+        }
+    };
+    if input_paths.is_empty() {
+        input_paths.push(input_path(&default_inputs_dir(), S::DAY));
+    }
+    let print_names = input_paths.len() > 1;
+
+    let mut failed = false;
+    for path in input_paths.iter() {
+        let name = input_name(path);
+        let input = match read_input(path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("error: failed to read the input file '{}': {}", name, e);
+                failed = true;
+                continue;
+            }
+        };
+        let report = match Report::solve::<S>(&input, None) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{}", e.render(&name, &input));
+                failed = true;
+                continue;
+            }
+        };
+
+        match format {
+            Format::Text => {
+                if print_names {
+                    println!("{}:", name);
+                }
+                for (template, answer) in templates.iter().zip(&report.answers) {
+                    if let Some(answer) = answer {
+                        let indent = if print_names { "    " } else { "" };
+                        println!("{}{}", indent, template.replace("{}", &answer.text));
+                    }
+                }
+            }
+            Format::Json => println!("{}", report.to_json(&name)),
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{parse_args, parse_token, Format, InputError, ParseError, Solution};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<u32>;
+        type Answer1 = u32;
+        type Answer2 = usize;
+
+        fn parse(input: &str) -> Result<Self::Input, InputError> {
+            Ok(input
+                .split(',')
+                .map(|num| parse_token(0, input, num, "an integer"))
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part_1(input: &Self::Input) -> Self::Answer1 {
+            input.iter().sum()
+        }
+
+        fn part_2(input: &Self::Input) -> Self::Answer2 {
+            input.len()
+        }
+    }
+
+    #[test]
+    fn solves_both_parts_by_default() {
+        let input = Sum::parse("1,2,3").unwrap();
+
+        assert_eq!(Sum::solve(&input), (6, 3));
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let Err(InputError::Parse(error)) = Sum::parse("1,x,3") else {
+            panic!("input should be rejected");
+        };
+
+        assert_eq!(error, ParseError::new(1, 3, "x", "an integer"));
+    }
+
+    #[test]
+    fn parses_day_arguments() {
+        let parse = |args: &str| parse_args(args.split_whitespace().map(str::to_string));
+
+        assert_eq!(parse(""), Ok((Format::Text, Vec::new())));
+        assert_eq!(
+            parse("--format json mine.txt - theirs.txt"),
+            Ok((
+                Format::Json,
+                vec![
+                    PathBuf::from("mine.txt"),
+                    PathBuf::from("-"),
+                    PathBuf::from("theirs.txt")
+                ]
+            ))
+        );
+        assert!(parse("--format").is_err());
+        assert!(parse("--format xml input.txt").is_err());
+        assert!(parse("--verbose input.txt").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::{InputError, Json, Solution, ToJson};
+
+/// Format in which the answers are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable sentences
+    Text,
+    /// A single JSON object per input
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            format => Err(format!("unknown format '{}', expected 'text' or 'json'", format)),
+        }
+    }
+}
+
+/// Answer for a single part, both as it's displayed and as it's emitted in JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub text: String,
+    pub value: Json,
+}
+
+impl Answer {
+    pub fn new<T: fmt::Display + ToJson>(answer: &T) -> Self {
+        Answer {
+            text: answer.to_string(),
+            value: answer.to_json(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Answers for a single day along with the time it took to find them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub day: u8,
+    /// A part is `None` if it wasn't requested
+    pub answers: [Option<Answer>; 2],
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Report {
+    /// Parses the input and solves either both parts or only the selected one
+    pub fn solve<S: Solution>(input: &str, part: Option<u8>) -> Result<Self, InputError> {
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answers = match part {
+            Some(1) => [Some(Answer::new(&S::part_1(&input))), None],
+            Some(_) => [None, Some(Answer::new(&S::part_2(&input)))],
+            None => {
+                let (part_1, part_2) = S::solve(&input);
+                [Some(Answer::new(&part_1)), Some(Answer::new(&part_2))]
+            }
+        };
+        let solve_time = start.elapsed();
+
+        Ok(Report {
+            day: S::DAY,
+            answers,
+            parse_time,
+            solve_time,
+        })
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+
+    /// Formats the report as a JSON object, along with the name of the input it's for
+    pub fn to_json(&self, input: &str) -> Json {
+        let answer = |answer: &Option<Answer>| answer.as_ref().map_or(Json::Null, |answer| answer.value.clone());
+        Json::object([
+            ("day", Json::from(self.day as u64)),
+            ("input", Json::from(input)),
+            ("part1", answer(&self.answers[0])),
+            ("part2", answer(&self.answers[1])),
+            (
+                "timings",
+                Json::object([
+                    ("parse_ns", Json::from(self.parse_time.as_nanos() as u64)),
+                    ("solve_ns", Json::from(self.solve_time.as_nanos() as u64)),
+                ]),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::report::{Answer, Format, Report};
+
+    #[test]
+    fn formats_report_as_json() {
+        let report = Report {
+            day: 11,
+            answers: [Some(Answer::new(&55312u64)), Some(Answer::new(&65601038650482u128))],
+            parse_time: Duration::from_nanos(1500),
+            solve_time: Duration::from_micros(20),
+        };
+
+        assert_eq!(
+            report.to_json("inputs/day-11.txt").to_string(),
+            r#"{"day":11,"input":"inputs/day-11.txt","part1":55312,"part2":"65601038650482","timings":{"parse_ns":1500,"solve_ns":20000}}"#
+        );
+    }
+
+    #[test]
+    fn parses_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+/// A small deterministic pseudo-random number generator (SplitMix64).
+///
+/// Used to generate synthetic inputs and randomized test cases, it's not suitable for anything security-related.
+#[derive(Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number within the range, which must not be empty
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(!range.is_empty(), "range must not be empty");
+        let size = range.end.abs_diff(range.start);
+        range.start.wrapping_add((self.next_u64() % size) as i64)
+    }
+
+    /// Returns an index below `len`, which must not be zero
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..len as i64) as usize
+    }
+
+    /// Returns `true` with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.index(idx + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::Rng;
+
+    #[test]
+    fn stays_within_range() {
+        let mut rng = Rng::new(42);
+        for _ in 0..1000 {
+            let value = rng.range(-5..5);
+            assert!((-5..5).contains(&value));
+        }
+    }
+}
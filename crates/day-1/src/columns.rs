@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::io::{BufRead as _, BufReader, Read};
+use std::str::FromStr;
+
+use common::{parse_token, InputError, ParseError};
+
+use crate::calculate_distance_and_similarity;
+
+/// Delimiter between the columns of a list dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Any run of whitespace
+    Whitespace,
+    /// A single character, e.g. `,` for CSV or `\t` for TSV
+    Char(char),
+}
+
+impl Delimiter {
+    pub const COMMA: Delimiter = Delimiter::Char(',');
+    pub const TAB: Delimiter = Delimiter::Char('\t');
+
+    /// Guesses the delimiter from a line of the input
+    pub fn detect(line: &str) -> Self {
+        if line.contains('\t') {
+            Delimiter::TAB
+        } else if line.contains(',') {
+            Delimiter::COMMA
+        } else {
+            Delimiter::Whitespace
+        }
+    }
+
+    fn split(self, line: &str) -> Vec<&str> {
+        match self {
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+            // Spaces around the values are common in hand-written CSV files
+            Delimiter::Char(delimiter) => line.split(delimiter).map(|value| value.trim_matches(' ')).collect(),
+        }
+    }
+}
+
+impl FromStr for Delimiter {
+    type Err = String;
+
+    fn from_str(delimiter: &str) -> Result<Self, Self::Err> {
+        match delimiter {
+            "whitespace" => Ok(Delimiter::Whitespace),
+            "csv" => Ok(Delimiter::COMMA),
+            "tsv" => Ok(Delimiter::TAB),
+            delimiter => {
+                let mut chars = delimiter.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(delimiter), None) => Ok(Delimiter::Char(delimiter)),
+                    _ => Err(format!(
+                        "unknown delimiter '{}', expected 'whitespace', 'csv', 'tsv' or a single character",
+                        delimiter
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// Layout of a list dump
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnFormat {
+    /// Detected from the first line if `None`
+    pub delimiter: Option<Delimiter>,
+    /// Whether the first line contains column names that should be skipped
+    pub has_header: bool,
+}
+
+/// Parses every row of a list dump and passes its values to the callback.
+///
+/// All rows must have the same number of columns, which is taken from the first row unless it's provided. Blank lines
+/// are skipped.
+pub(crate) fn read_rows<R: Read>(
+    reader: R,
+    format: ColumnFormat,
+    mut column_count: Option<usize>,
+    mut on_row: impl FnMut(Vec<i64>) -> Result<(), InputError>,
+) -> Result<(), InputError> {
+    let mut delimiter = format.delimiter;
+    let mut skip_header = format.has_header;
+    for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if skip_header {
+            skip_header = false;
+            continue;
+        }
+
+        let delimiter = *delimiter.get_or_insert_with(|| Delimiter::detect(&line));
+        let values = delimiter.split(&line);
+        let column_count = *column_count.get_or_insert(values.len());
+        if let Some(extra_value) = values.get(column_count) {
+            return Err(ParseError::at_token(line_idx, &line, extra_value, "end of the line").into());
+        }
+        if values.len() < column_count {
+            let expected = format!("{} columns", column_count);
+            return Err(ParseError::at_end_of_line(line_idx, &line, expected).into());
+        }
+
+        on_row(
+            values
+                .into_iter()
+                .map(|value| parse_token(line_idx, &line, value, "an integer"))
+                .collect::<Result<_, _>>()?,
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads the input data with any number of columns and splits it into sorted lists, one per column.
+///
+/// All lines must have the same number of columns, blank lines are skipped.
+pub fn read_columns<R: Read>(reader: R, format: ColumnFormat) -> Result<Vec<Vec<i64>>, InputError> {
+    let mut columns: Vec<Vec<i64>> = Vec::new();
+    read_rows(reader, format, None, |row| {
+        if columns.is_empty() {
+            columns = vec![Vec::new(); row.len()];
+        }
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        Ok(())
+    })?;
+
+    for column in columns.iter_mut() {
+        column.sort();
+    }
+
+    Ok(columns)
+}
+
+/// Counts how many times each value appears in the list
+pub fn count_occurences(list: &[i64]) -> HashMap<i64, i64> {
+    let mut occurence_map = HashMap::new();
+    for item in list {
+        *occurence_map.entry(*item).or_default() += 1;
+    }
+    occurence_map
+}
+
+/// Distance and similarity between two columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnPair {
+    pub left: usize,
+    pub right: usize,
+    pub distance: i64,
+    /// Similarity of the left column, weighted by the occurences in the right one
+    pub similarity: i64,
+}
+
+/// Calculates the distance and similarity for every pair of the sorted columns, with the left column always
+/// preceding the right one
+pub fn pairwise_distance_and_similarity(columns: &[Vec<i64>]) -> Vec<ColumnPair> {
+    let occurence_maps = columns
+        .iter()
+        .map(|column| count_occurences(column))
+        .collect::<Vec<_>>();
+
+    let mut pairs = Vec::new();
+    for left in 0..columns.len() {
+        for right in left + 1..columns.len() {
+            let (distance, similarity) =
+                calculate_distance_and_similarity(&columns[left], &columns[right], Some(&occurence_maps[right]));
+            pairs.push(ColumnPair {
+                left,
+                right,
+                distance,
+                similarity,
+            });
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{InputError, ParseError};
+
+    use crate::columns::{pairwise_distance_and_similarity, read_columns, ColumnFormat, ColumnPair, Delimiter};
+    use crate::read_input_into_lists;
+
+    const TEST_INPUT: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+    #[test]
+    fn reads_any_delimiter() {
+        let (list_1, list_2) = read_input_into_lists(TEST_INPUT.as_bytes(), None).unwrap();
+        let expected = vec![list_1, list_2];
+
+        for input in [
+            TEST_INPUT.to_string(),
+            TEST_INPUT.replace("   ", " "),
+            TEST_INPUT.replace("   ", "\t"),
+            TEST_INPUT.replace("   ", ", "),
+            format!("left,right\n{}\n\n", TEST_INPUT.replace("   ", ",")),
+        ] {
+            let format = ColumnFormat {
+                delimiter: None,
+                has_header: input.starts_with("left"),
+            };
+            assert_eq!(read_columns(input.as_bytes(), format).unwrap(), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let format = ColumnFormat {
+            delimiter: Some(Delimiter::COMMA),
+            has_header: false,
+        };
+        let Err(InputError::Parse(error)) = read_columns("1,2,3\n4,5\n".as_bytes(), format) else {
+            panic!("input should be rejected");
+        };
+        assert_eq!(error, ParseError::new(2, 4, "", "3 columns"));
+
+        let Err(InputError::Parse(error)) = read_columns("1,2\n4,5,6\n".as_bytes(), format) else {
+            panic!("input should be rejected");
+        };
+        assert_eq!(error, ParseError::new(2, 5, "6", "end of the line"));
+
+        let Err(InputError::Parse(error)) = read_columns("1,,3\n".as_bytes(), format) else {
+            panic!("input should be rejected");
+        };
+        assert_eq!(error, ParseError::new(1, 3, "", "an integer"));
+    }
+
+    #[test]
+    fn calculates_metrics_for_every_pair() {
+        let columns = read_columns("3 4 3\n4 3 1\n2 5 2".as_bytes(), ColumnFormat::default()).unwrap();
+
+        assert_eq!(
+            pairwise_distance_and_similarity(&columns),
+            [
+                ColumnPair {
+                    left: 0,
+                    right: 1,
+                    distance: 3,
+                    similarity: 7,
+                },
+                ColumnPair {
+                    left: 0,
+                    right: 2,
+                    distance: 3,
+                    similarity: 5,
+                },
+                ColumnPair {
+                    left: 1,
+                    right: 2,
+                    distance: 6,
+                    similarity: 3,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::InputError;
+
+use crate::columns::{read_rows, ColumnFormat};
+
+/// Size of a single value in the spilled runs
+const VALUE_SIZE: usize = size_of::<i64>();
+
+/// Buffer size used for reading runs when the budget allows it
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Maximum number of runs that are merged at once, as every run keeps a file open
+const MAX_FAN_IN: usize = 256;
+
+/// Configuration of the external sort
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSortOptions {
+    /// Approximate number of bytes used for the buffered values
+    pub memory_budget: usize,
+    /// Directory for the spilled runs
+    pub temp_dir: PathBuf,
+}
+
+impl Default for ExternalSortOptions {
+    fn default() -> Self {
+        ExternalSortOptions {
+            memory_budget: 256 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+/// Results of the external sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalSortSummary {
+    pub distance: i64,
+    pub similarity: i64,
+    /// Number of sorted runs spilled to disk for both lists, zero if everything fit into the budget
+    pub spilled_runs: usize,
+}
+
+/// A sorted run of values stored in a temporary file, which is removed once the run is dropped
+struct Run {
+    path: PathBuf,
+}
+
+impl Run {
+    fn create(
+        options: &ExternalSortOptions,
+        sorted_values: impl IntoIterator<Item = io::Result<i64>>,
+    ) -> io::Result<Self> {
+        static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = options.temp_dir.join(format!(
+            "aoc-day-1-{}-{}.run",
+            std::process::id(),
+            RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        // The run is created first, so that the file is cleaned up even if writing fails
+        let run = Run { path };
+        let mut writer = BufWriter::new(File::create(&run.path)?);
+        for value in sorted_values {
+            writer.write_all(&value?.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(run)
+    }
+
+    fn values(&self, buffer_size: usize) -> io::Result<RunValues> {
+        Ok(RunValues {
+            reader: BufReader::with_capacity(buffer_size, File::open(&self.path)?),
+        })
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+struct RunValues {
+    reader: BufReader<File>,
+}
+
+impl Iterator for RunValues {
+    type Item = io::Result<i64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; VALUE_SIZE];
+        match self.reader.read_exact(&mut bytes) {
+            Ok(()) => Some(Ok(i64::from_le_bytes(bytes))),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Merges sorted runs into a single sorted stream
+struct Merge {
+    runs: Vec<RunValues>,
+    heap: BinaryHeap<Reverse<(i64, usize)>>,
+    /// Set once the merge produced an error, the merge must not be continued afterwards
+    failed: bool,
+}
+
+impl Merge {
+    fn new(runs: &[Run], buffer_size: usize) -> io::Result<Self> {
+        let mut merge = Merge {
+            runs: runs
+                .iter()
+                .map(|run| run.values(buffer_size))
+                .collect::<io::Result<_>>()?,
+            heap: BinaryHeap::new(),
+            failed: false,
+        };
+        for run_idx in 0..merge.runs.len() {
+            merge.refill(run_idx)?;
+        }
+        Ok(merge)
+    }
+
+    fn refill(&mut self, run_idx: usize) -> io::Result<()> {
+        if let Some(value) = self.runs[run_idx].next() {
+            self.heap.push(Reverse((value?, run_idx)));
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<i64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let Reverse((value, run_idx)) = self.heap.pop()?;
+        if let Err(e) = self.refill(run_idx) {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        Some(Ok(value))
+    }
+}
+
+/// A list that is either sorted in memory or spilled to disk as sorted runs
+struct SortedList {
+    /// Values that weren't spilled yet
+    buffer: Vec<i64>,
+    /// Number of buffered values that are spilled at once
+    threshold: usize,
+    runs: Vec<Run>,
+    /// Number of runs spilled directly from the buffer
+    spilled_runs: usize,
+}
+
+impl SortedList {
+    /// Creates a list that spills its values once `threshold` of them are buffered
+    fn new(threshold: usize) -> Self {
+        SortedList {
+            buffer: Vec::new(),
+            threshold,
+            runs: Vec::new(),
+            spilled_runs: 0,
+        }
+    }
+
+    fn push(&mut self, value: i64, options: &ExternalSortOptions) -> io::Result<()> {
+        self.buffer.push(value);
+        if self.buffer.len() >= self.threshold {
+            self.spill(options)?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self, options: &ExternalSortOptions) -> io::Result<()> {
+        self.buffer.sort_unstable();
+        let run = Run::create(options, self.buffer.drain(..).map(Ok))?;
+        self.runs.push(run);
+        self.spilled_runs += 1;
+        Ok(())
+    }
+
+    /// Finishes the sort, merging the runs until all of them can be read at once
+    fn finish(mut self, options: &ExternalSortOptions) -> io::Result<Self> {
+        if self.runs.is_empty() {
+            self.buffer.sort_unstable();
+            return Ok(self);
+        }
+        if !self.buffer.is_empty() {
+            self.spill(options)?;
+        }
+        // Everything is on disk now, so the buffer's memory is left to the merge
+        self.buffer = Vec::new();
+
+        let fan_in = fan_in(options);
+        let buffer_size = read_buffer_size(options, fan_in);
+        while self.runs.len() > fan_in {
+            let mut merged = Vec::new();
+            for runs in self.runs.chunks(fan_in) {
+                merged.push(Run::create(options, Merge::new(runs, buffer_size)?)?);
+            }
+            self.runs = merged;
+        }
+        Ok(self)
+    }
+
+    fn values(&self, options: &ExternalSortOptions) -> io::Result<Box<dyn Iterator<Item = io::Result<i64>> + '_>> {
+        if self.runs.is_empty() {
+            Ok(Box::new(self.buffer.iter().copied().map(Ok)))
+        } else {
+            // Two lists are read at the same time, each of them gets a half of the budget
+            let buffer_size = read_buffer_size(options, self.runs.len() * 2);
+            Ok(Box::new(Merge::new(&self.runs, buffer_size)?))
+        }
+    }
+}
+
+fn fan_in(options: &ExternalSortOptions) -> usize {
+    (options.memory_budget / READ_BUFFER_SIZE).clamp(2, MAX_FAN_IN)
+}
+
+fn read_buffer_size(options: &ExternalSortOptions, readers: usize) -> usize {
+    (options.memory_budget / readers).clamp(VALUE_SIZE, READ_BUFFER_SIZE)
+}
+
+/// Sums the left values weighted by how many times they appear in the right list, both lists must be sorted
+fn sorted_similarity(
+    left: impl Iterator<Item = io::Result<i64>>,
+    right: impl Iterator<Item = io::Result<i64>>,
+) -> io::Result<i64> {
+    let mut right: Peekable<_> = right.peekable();
+    let mut similarity = 0;
+    // Number of occurences of the last left value, which is reused for duplicates
+    let mut last = None;
+    for value in left {
+        let value = value?;
+        let occurences = match last {
+            Some((last_value, occurences)) if last_value == value => occurences,
+            _ => {
+                let mut occurences = 0;
+                while let Some(right_value) =
+                    right.next_if(|right_value| right_value.as_ref().map_or(true, |right_value| *right_value <= value))
+                {
+                    if right_value? == value {
+                        occurences += 1;
+                    }
+                }
+                last = Some((value, occurences));
+                occurences
+            }
+        };
+        similarity += value * occurences;
+    }
+    Ok(similarity)
+}
+
+/// Calculates the total distance and the similarity score of two columns in bounded memory.
+///
+/// Values are buffered until the memory budget is exhausted, after which they are sorted and spilled to temporary
+/// files that are merged afterwards.
+pub fn external_distance_and_similarity<R: Read>(
+    reader: R,
+    format: ColumnFormat,
+    options: &ExternalSortOptions,
+) -> Result<ExternalSortSummary, InputError> {
+    // Every line adds a value to both lists
+    let threshold = (options.memory_budget / (2 * VALUE_SIZE)).max(1);
+    let mut lists = [(); 2].map(|_| SortedList::new(threshold));
+
+    read_rows(reader, format, Some(2), |row| {
+        for (list, value) in lists.iter_mut().zip(row) {
+            list.push(value, options)?;
+        }
+        Ok(())
+    })?;
+
+    let [left, right] = lists.map(|list| list.finish(options));
+    let (left, right) = (left?, right?);
+
+    let mut distance = 0;
+    for (left_value, right_value) in left.values(options)?.zip(right.values(options)?) {
+        distance += (left_value? - right_value?).abs();
+    }
+    let similarity = sorted_similarity(left.values(options)?, right.values(options)?)?;
+
+    Ok(ExternalSortSummary {
+        distance,
+        similarity,
+        spilled_runs: left.spilled_runs + right.spilled_runs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use common::Rng;
+
+    use crate::columns::ColumnFormat;
+    use crate::external::{external_distance_and_similarity, ExternalSortOptions, ExternalSortSummary, SortedList};
+    use crate::{calculate_distance_and_similarity, count_occurences, read_columns};
+
+    fn options(name: &str, memory_budget: usize) -> ExternalSortOptions {
+        let temp_dir = std::env::temp_dir().join(format!("aoc-day-1-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&temp_dir).unwrap();
+        ExternalSortOptions {
+            memory_budget,
+            temp_dir,
+        }
+    }
+
+    #[test]
+    fn calculates_sample_in_memory_and_on_disk() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+        for (memory_budget, spilled) in [(1024, false), (16, true)] {
+            let options = options("sample", memory_budget);
+            let summary =
+                external_distance_and_similarity(input.as_bytes(), ColumnFormat::default(), &options).unwrap();
+
+            assert_eq!((summary.distance, summary.similarity), (11, 31));
+            assert_eq!(summary.spilled_runs > 0, spilled);
+            // Spilled runs are removed once they are merged
+            assert_eq!(fs::read_dir(&options.temp_dir).unwrap().count(), 0);
+            fs::remove_dir(&options.temp_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn matches_in_memory_results() {
+        let mut rng = Rng::new(1);
+        let input = (0..2000)
+            .map(|_| format!("{},{}", rng.range(0..500), rng.range(0..500)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let columns = read_columns(input.as_bytes(), ColumnFormat::default()).unwrap();
+        let (distance, similarity) =
+            calculate_distance_and_similarity(&columns[0], &columns[1], Some(&count_occurences(&columns[1])));
+
+        // A tiny budget of 32 values per list forces 63 runs per list and multiple merge passes
+        let options = options("random", 512);
+        let summary = external_distance_and_similarity(input.as_bytes(), ColumnFormat::default(), &options).unwrap();
+        assert_eq!(
+            summary,
+            ExternalSortSummary {
+                distance,
+                similarity,
+                spilled_runs: summary.spilled_runs,
+            }
+        );
+        assert_eq!(summary.spilled_runs, 2 * 63);
+        fs::remove_dir(&options.temp_dir).unwrap();
+    }
+
+    #[test]
+    fn spills_buffer_at_threshold() {
+        let options = options("buffer", 0);
+        let mut list = SortedList::new(30);
+
+        for value in 0..100 {
+            list.push(value, &options).unwrap();
+            assert!(list.buffer.len() < 30);
+        }
+        assert_eq!(list.spilled_runs, 3);
+
+        // The merge only reads the runs, so the buffer is released
+        let list = list.finish(&options).unwrap();
+        assert_eq!((list.spilled_runs, list.buffer.capacity()), (4, 0));
+        drop(list);
+        fs::remove_dir(&options.temp_dir).unwrap();
+    }
+}
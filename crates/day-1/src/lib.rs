@@ -1,46 +1,61 @@
+mod columns;
+mod external;
+mod metric;
+mod reconciler;
+mod report;
+
 use std::collections::HashMap;
 use std::io::{BufRead as _, BufReader, Read};
 
-/// Reads the input data and splits it into two sorted lists
+pub use columns::{
+    count_occurences, pairwise_distance_and_similarity, read_columns, ColumnFormat, ColumnPair, Delimiter,
+};
+use common::{parse_token, InputError, ParseError, Solution};
+pub use external::{external_distance_and_similarity, ExternalSortOptions, ExternalSortSummary};
+pub use metric::{
+    find_metric, BidirectionalSimilarity, Distance, Jaccard, Kendall, Lists, MaxDistance, Metric, MetricValue,
+    Similarity, Spearman, SquaredDistance, METRICS,
+};
+pub use reconciler::{ListReconciler, Side};
+pub use report::{CountMismatch, MultisetDiff, PairRow, ReconciliationReport};
+
+/// Reads the input data and splits it into two sorted lists.
+///
+/// This is the strict puzzle format, see [`read_columns`] for other delimiters and any number of columns.
 pub fn read_input_into_lists<R: Read>(
     reader: R,
     mut occurence_map: Option<&mut HashMap<i64, i64>>,
-) -> (Vec<i64>, Vec<i64>) {
-    let (mut list_1, mut list_2) =
-        BufReader::new(reader)
-            .lines()
-            .fold((Vec::new(), Vec::new()), |(mut list_1, mut list_2), line| {
-                let line = line.expect("failed to read a line");
-                // Columns are delimited using 3 spaces
-                let mut parts = line.split("   ");
-
-                let list_1_item = parts
-                    .next()
-                    .expect("invalid input format: first list item is missing")
-                    .parse::<i64>()
-                    .expect("item is not a valid integer");
-                list_1.push(list_1_item);
-
-                let list_2_item = parts
-                    .next()
-                    .expect("invalid input format: second list item is missing")
-                    .parse::<i64>()
-                    .expect("item is not a valid integer");
-                list_2.push(list_2_item);
-
-                // Part 2: similarity score
-                if let Some(occurence_map) = occurence_map.as_mut() {
-                    *occurence_map.entry(list_2_item).or_default() += 1;
-                }
-
-                (list_1, list_2)
-            });
+) -> Result<(Vec<i64>, Vec<i64>), InputError> {
+    let mut list_1 = Vec::new();
+    let mut list_2 = Vec::new();
+    for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        // Columns are delimited using 3 spaces
+        let mut parts = line.split("   ");
+
+        let list_1_item = match parts.next() {
+            Some(item) => parse_token::<i64>(line_idx, &line, item, "an integer")?,
+            None => return Err(ParseError::at_end_of_line(line_idx, &line, "first list item").into()),
+        };
+        list_1.push(list_1_item);
+
+        let list_2_item = match parts.next() {
+            Some(item) => parse_token::<i64>(line_idx, &line, item, "an integer")?,
+            None => return Err(ParseError::at_end_of_line(line_idx, &line, "second list item").into()),
+        };
+        list_2.push(list_2_item);
+
+        // Part 2: similarity score
+        if let Some(occurence_map) = occurence_map.as_mut() {
+            *occurence_map.entry(list_2_item).or_default() += 1;
+        }
+    }
 
     // Sort both lists
     list_1.sort();
     list_2.sort();
 
-    (list_1, list_2)
+    Ok((list_1, list_2))
 }
 
 /// Calculates the sum of distances between corresponding items in two lists and the similarity score
@@ -66,31 +81,77 @@ pub fn calculate_distance_and_similarity(
         })
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = (Vec<i64>, Vec<i64>, HashMap<i64, i64>);
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        let mut occurence_map = HashMap::new();
+        let (list_1, list_2) = read_input_into_lists(input.as_bytes(), Some(&mut occurence_map))?;
+        Ok((list_1, list_2, occurence_map))
+    }
+
+    fn part_1((list_1, list_2, _): &Self::Input) -> Self::Answer1 {
+        calculate_distance_and_similarity(list_1, list_2, None).0
+    }
+
+    fn part_2((list_1, list_2, occurence_map): &Self::Input) -> Self::Answer2 {
+        calculate_distance_and_similarity(list_1, list_2, Some(occurence_map)).1
+    }
+
+    fn solve((list_1, list_2, occurence_map): &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        calculate_distance_and_similarity(list_1, list_2, Some(occurence_map))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use common::{InputError, ParseError};
+
     use crate::{calculate_distance_and_similarity, read_input_into_lists};
 
     const TEST_INPUT: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
 
     #[test]
-    #[should_panic(expected = "invalid input format: second list item is missing")]
-    fn panics_on_invalid_input() {
+    fn fails_on_invalid_input() {
         let input = "1";
-        read_input_into_lists(input.as_bytes(), None);
+        let Err(InputError::Parse(error)) = read_input_into_lists(input.as_bytes(), None) else {
+            panic!("input should be rejected");
+        };
+
+        assert_eq!(error, ParseError::new(1, 2, "", "second list item"));
     }
 
     #[test]
-    #[should_panic(expected = "item is not a valid integer")]
-    fn panics_on_non_integer_data() {
-        let input = "bad   input";
-        read_input_into_lists(input.as_bytes(), None);
+    fn fails_on_non_integer_data() {
+        let input = "1   2\nbad   input";
+        let Err(InputError::Parse(error)) = read_input_into_lists(input.as_bytes(), None) else {
+            panic!("input should be rejected");
+        };
+
+        assert_eq!(error, ParseError::new(2, 1, "bad", "an integer"));
+    }
+
+    #[test]
+    fn points_to_trailing_space() {
+        let input = "1   2 \n3   4";
+        let Err(InputError::Parse(error)) = read_input_into_lists(input.as_bytes(), None) else {
+            panic!("input should be rejected");
+        };
+
+        assert_eq!(error, ParseError::new(1, 5, "2 ", "an integer"));
     }
 
     #[test]
     fn lists_are_parsed_correctly() {
-        let (list_1, list_2) = read_input_into_lists(TEST_INPUT.as_bytes(), None);
+        let (list_1, list_2) = read_input_into_lists(TEST_INPUT.as_bytes(), None).unwrap();
 
         assert_eq!(list_1, [1, 2, 3, 3, 3, 4]);
         assert_eq!(list_2, [3, 3, 3, 4, 5, 9]);
@@ -99,7 +160,7 @@ mod tests {
     #[test]
     fn correctly_calculates_occurences() {
         let mut occurence_map = HashMap::new();
-        let _ = read_input_into_lists(TEST_INPUT.as_bytes(), Some(&mut occurence_map));
+        let _ = read_input_into_lists(TEST_INPUT.as_bytes(), Some(&mut occurence_map)).unwrap();
 
         assert_eq!(occurence_map.get(&1).copied().unwrap_or_default(), 0);
         assert_eq!(occurence_map.get(&2).copied().unwrap_or_default(), 0);
@@ -110,7 +171,7 @@ mod tests {
     #[test]
     fn correctly_calculates_distance_and_similarity() {
         let mut occurence_map = HashMap::new();
-        let (list_1, list_2) = read_input_into_lists(TEST_INPUT.as_bytes(), Some(&mut occurence_map));
+        let (list_1, list_2) = read_input_into_lists(TEST_INPUT.as_bytes(), Some(&mut occurence_map)).unwrap();
 
         let (distance, similarity) = calculate_distance_and_similarity(&list_1, &list_2, Some(&occurence_map));
 
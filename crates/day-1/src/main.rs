@@ -1,20 +1,238 @@
-use std::collections::HashMap;
 use std::env;
 use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use day_1::{calculate_distance_and_similarity, read_input_into_lists};
+use common::{default_inputs_dir, input_name, input_path, read_input, STDIN};
+use day_1::{
+    external_distance_and_similarity, find_metric, read_columns, ColumnFormat, Day1, ExternalSortOptions, Lists,
+    Metric, ReconciliationReport, METRICS,
+};
 
-fn main() {
-    let input_path = env::args().nth(1).expect("input file path is missing");
-    let input_file = File::open(input_path).expect("error while reading the file");
+const USAGE: &str = "Usage:
+    day-1 [--format <text|json>] [INPUT]...
+    day-1 --external [--memory-budget <BYTES>] [--temp-dir <DIR>] [LIST OPTIONS] [INPUT]
+    day-1 --report <pairs-csv|diff-csv|json> [LIST OPTIONS] [INPUT]
+    day-1 --metric <NAME>[,<NAME>...] [LIST OPTIONS] [INPUT]
 
-    // Part 2: similarity score
-    let mut occurence_map = HashMap::new();
-    let (list_1, list_2) = read_input_into_lists(&input_file, Some(&mut occurence_map));
-    let (distance_sum, similarity_score) = calculate_distance_and_similarity(&list_1, &list_2, Some(&occurence_map));
+Options:
+    --external                Sort the lists on disk, reading the input as a stream
+    --memory-budget <BYTES>   Memory for buffering the lists before they are spilled to disk, accepts K/M/G suffixes
+    --temp-dir <DIR>          Directory for the spilled sorted runs [default: system temp directory]
+    --report <KIND>           Print every pair of the sorted lists or the difference between the lists
+    --metric <NAMES>          Compare the lists using the given metrics, 'all' selects every metric
 
-    println!(
-        "Total distance between the left list and the right list: {}\nSimilarity score: {}",
-        distance_sum, similarity_score
-    )
+List options:
+    --delimiter <DELIMITER>   'whitespace', 'csv', 'tsv' or a single character [default: detected]
+    --header                  Skip the first line of the input";
+
+/// Usage along with the available metrics, so that newly added metrics are listed as well
+fn usage() -> String {
+    let mut usage = format!("{}\n\nMetrics:", USAGE);
+    for metric in METRICS {
+        usage.push_str(&format!("\n    {:<26}{}", metric.name(), metric.description()));
+    }
+    usage
+}
+
+/// Templates of the answers in the text format
+const TEMPLATES: [&str; 2] = [
+    "Total distance between the left list and the right list: {}",
+    "Similarity score: {}",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportKind {
+    PairsCsv,
+    DiffCsv,
+    Json,
+}
+
+enum Mode {
+    External(ExternalSortOptions),
+    Report(ReportKind),
+    Metrics(Vec<&'static dyn Metric>),
+}
+
+/// Options of the modes that work with list dumps rather than with the puzzle input
+struct ListOptions {
+    mode: Mode,
+    format: ColumnFormat,
+    input: PathBuf,
+}
+
+fn parse_size(size: &str) -> Option<usize> {
+    let (number, multiplier) = match size.char_indices().last()? {
+        (idx, 'K' | 'k') => (&size[..idx], 1 << 10),
+        (idx, 'M' | 'm') => (&size[..idx], 1 << 20),
+        (idx, 'G' | 'g') => (&size[..idx], 1 << 30),
+        _ => (size, 1),
+    };
+    number.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+/// Flags that are only used by the list modes
+const LIST_FLAGS: [&str; 7] = [
+    "--external",
+    "--memory-budget",
+    "--temp-dir",
+    "--report",
+    "--metric",
+    "--delimiter",
+    "--header",
+];
+
+/// Returns `None` if the arguments don't select any of the list modes
+fn parse_list_args(args: Vec<String>) -> Result<Option<ListOptions>, String> {
+    if !args.iter().any(|arg| LIST_FLAGS.contains(&arg.as_str())) {
+        return Ok(None);
+    }
+
+    let mut external = None;
+    let mut report = None;
+    let mut metrics = None;
+    let mut format = ColumnFormat::default();
+    let mut input = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--external" => {
+                external.get_or_insert_with(ExternalSortOptions::default);
+            }
+            "--memory-budget" => {
+                external.get_or_insert_with(ExternalSortOptions::default).memory_budget = args
+                    .next()
+                    .as_deref()
+                    .and_then(parse_size)
+                    .filter(|budget| *budget > 0)
+                    .ok_or("--memory-budget requires a positive size, e.g. 512M")?
+            }
+            "--temp-dir" => {
+                external.get_or_insert_with(ExternalSortOptions::default).temp_dir = args
+                    .next()
+                    .map(PathBuf::from)
+                    .ok_or("--temp-dir requires a directory")?
+            }
+            "--report" => {
+                report = Some(match args.next().as_deref() {
+                    Some("pairs-csv") => ReportKind::PairsCsv,
+                    Some("diff-csv") => ReportKind::DiffCsv,
+                    Some("json") => ReportKind::Json,
+                    _ => return Err("--report requires 'pairs-csv', 'diff-csv' or 'json'".to_string()),
+                })
+            }
+            "--metric" => metrics = Some(parse_metrics(&args.next().ok_or("--metric requires metric names")?)?),
+            "--delimiter" => format.delimiter = Some(args.next().ok_or("--delimiter requires a delimiter")?.parse()?),
+            "--header" => format.has_header = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            path => {
+                if input.replace(PathBuf::from(path)).is_some() {
+                    return Err("only a single input file can be provided".to_string());
+                }
+            }
+        }
+    }
+
+    let mode = match (external, report, metrics) {
+        (Some(options), None, None) => Mode::External(options),
+        (None, Some(kind), None) => Mode::Report(kind),
+        (None, None, Some(metrics)) => Mode::Metrics(metrics),
+        (None, None, None) => {
+            return Err("--delimiter and --header require --external, --report or --metric".to_string())
+        }
+        _ => return Err("--external, --report and --metric can't be used together".to_string()),
+    };
+    let input = input.unwrap_or_else(|| input_path(&default_inputs_dir(), 1));
+    Ok(Some(ListOptions { mode, format, input }))
+}
+
+/// Parses a comma-separated list of metric names, 'all' selects every metric
+fn parse_metrics(names: &str) -> Result<Vec<&'static dyn Metric>, String> {
+    if names == "all" {
+        return Ok(METRICS.to_vec());
+    }
+    names
+        .split(',')
+        .map(|name| {
+            find_metric(name).ok_or_else(|| {
+                let available = METRICS.map(|metric| metric.name()).join(", ");
+                format!("unknown metric '{}', available metrics: {}", name, available)
+            })
+        })
+        .collect()
+}
+
+/// Calculates both answers for lists that don't fit into memory, reading the input as a stream
+fn run_external(options: &ExternalSortOptions, format: ColumnFormat, path: &Path) -> Result<(), String> {
+    let name = input_name(path);
+    let reader: Box<dyn Read> = if path == Path::new(STDIN) {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(path).map_err(|e| format!("error: failed to read the input file '{}': {}", name, e))?;
+        Box::new(file)
+    };
+
+    // The input is streamed, so the offending line can't be shown
+    let summary =
+        external_distance_and_similarity(reader, format, options).map_err(|e| format!("error: {}: {}", name, e))?;
+    println!("{}", TEMPLATES[0].replace("{}", &summary.distance.to_string()));
+    println!("{}", TEMPLATES[1].replace("{}", &summary.similarity.to_string()));
+    eprintln!("Spilled {} sorted runs to disk", summary.spilled_runs);
+    Ok(())
+}
+
+fn run_report(kind: ReportKind, format: ColumnFormat, path: &Path) -> Result<(), String> {
+    let name = input_name(path);
+    let input = read_input(path).map_err(|e| format!("error: failed to read the input file '{}': {}", name, e))?;
+    let columns = read_columns(input.as_bytes(), format).map_err(|e| e.render(&name, &input))?;
+    let [list_1, list_2] = match <[Vec<i64>; 2]>::try_from(columns) {
+        Ok(lists) => lists,
+        Err(columns) if columns.is_empty() => Default::default(),
+        Err(columns) => return Err(format!("error: expected 2 columns, found {}", columns.len())),
+    };
+
+    let report = ReconciliationReport::new(&list_1, &list_2);
+    match kind {
+        ReportKind::PairsCsv => print!("{}", report.pairs_csv()),
+        ReportKind::DiffCsv => print!("{}", report.diff_csv()),
+        ReportKind::Json => println!("{}", report.to_json().pretty()),
+    }
+    Ok(())
+}
+
+fn run_metrics(metrics: &[&'static dyn Metric], format: ColumnFormat, path: &Path) -> Result<(), String> {
+    let name = input_name(path);
+    let input = read_input(path).map_err(|e| format!("error: failed to read the input file '{}': {}", name, e))?;
+    let lists = Lists::read(input.as_bytes(), format).map_err(|e| e.render(&name, &input))?;
+
+    for metric in metrics {
+        println!("{}: {}", metric.name(), metric.compute(&lists));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_list_args(env::args().skip(1).collect()) {
+        Ok(Some(options)) => options,
+        Ok(None) => return common::run::<Day1>(TEMPLATES),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, usage());
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match &options.mode {
+        Mode::External(external) => run_external(external, options.format, &options.input),
+        Mode::Report(kind) => run_report(*kind, options.format, &options.input),
+        Mode::Metrics(metrics) => run_metrics(metrics, options.format, &options.input),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+use common::InputError;
+
+use crate::columns::{read_rows, ColumnFormat};
+use crate::count_occurences;
+
+/// Two lists, both in the order of the input rows and sorted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lists {
+    pub left: Vec<i64>,
+    pub right: Vec<i64>,
+    pub sorted_left: Vec<i64>,
+    pub sorted_right: Vec<i64>,
+}
+
+impl Lists {
+    pub fn new(left: Vec<i64>, right: Vec<i64>) -> Self {
+        let mut sorted_left = left.clone();
+        sorted_left.sort();
+        let mut sorted_right = right.clone();
+        sorted_right.sort();
+        Lists {
+            left,
+            right,
+            sorted_left,
+            sorted_right,
+        }
+    }
+
+    /// Reads two columns of a list dump
+    pub fn read<R: Read>(reader: R, format: ColumnFormat) -> Result<Self, InputError> {
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        read_rows(reader, format, Some(2), |row| {
+            left.push(row[0]);
+            right.push(row[1]);
+            Ok(())
+        })?;
+        Ok(Lists::new(left, right))
+    }
+
+    /// Pairs of values with the same rank in the sorted lists
+    fn sorted_pairs(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.sorted_left.iter().copied().zip(self.sorted_right.iter().copied())
+    }
+}
+
+/// Result of a metric
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricValue {
+    Integer(i128),
+    /// `NaN` if the metric is undefined for the lists
+    Real(f64),
+}
+
+impl fmt::Display for MetricValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricValue::Integer(value) => write!(f, "{}", value),
+            MetricValue::Real(value) if value.is_nan() => f.write_str("undefined"),
+            MetricValue::Real(value) => write!(f, "{:.6}", value),
+        }
+    }
+}
+
+/// A way to compare the two lists
+pub trait Metric {
+    /// Name used to select the metric
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn compute(&self, lists: &Lists) -> MetricValue;
+}
+
+/// Sum of absolute differences between the sorted lists (part 1)
+pub struct Distance;
+
+impl Metric for Distance {
+    fn name(&self) -> &'static str {
+        "distance"
+    }
+
+    fn description(&self) -> &'static str {
+        "sum of absolute differences of the sorted lists"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        MetricValue::Integer(
+            lists
+                .sorted_pairs()
+                .map(|(left, right)| left.abs_diff(right) as i128)
+                .sum(),
+        )
+    }
+}
+
+pub struct SquaredDistance;
+
+impl Metric for SquaredDistance {
+    fn name(&self) -> &'static str {
+        "squared"
+    }
+
+    fn description(&self) -> &'static str {
+        "sum of squared differences of the sorted lists"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        MetricValue::Integer(
+            lists
+                .sorted_pairs()
+                .map(|(left, right)| (left.abs_diff(right) as i128).pow(2))
+                .sum(),
+        )
+    }
+}
+
+/// Chebyshev (L∞) distance
+pub struct MaxDistance;
+
+impl Metric for MaxDistance {
+    fn name(&self) -> &'static str {
+        "max"
+    }
+
+    fn description(&self) -> &'static str {
+        "largest absolute difference of the sorted lists"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        MetricValue::Integer(
+            lists
+                .sorted_pairs()
+                .map(|(left, right)| left.abs_diff(right) as i128)
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+fn weighted_occurences(list: &[i64], occurence_map: &HashMap<i64, i64>) -> i128 {
+    list.iter()
+        .map(|value| *value as i128 * occurence_map.get(value).copied().unwrap_or_default() as i128)
+        .sum()
+}
+
+/// Left values weighted by their occurences in the right list (part 2)
+pub struct Similarity;
+
+impl Metric for Similarity {
+    fn name(&self) -> &'static str {
+        "similarity"
+    }
+
+    fn description(&self) -> &'static str {
+        "left values weighted by their occurences in the right list"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        MetricValue::Integer(weighted_occurences(&lists.left, &count_occurences(&lists.right)))
+    }
+}
+
+pub struct BidirectionalSimilarity;
+
+impl Metric for BidirectionalSimilarity {
+    fn name(&self) -> &'static str {
+        "bidirectional"
+    }
+
+    fn description(&self) -> &'static str {
+        "similarity of the left list to the right one plus the other way around"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        MetricValue::Integer(
+            weighted_occurences(&lists.left, &count_occurences(&lists.right))
+                + weighted_occurences(&lists.right, &count_occurences(&lists.left)),
+        )
+    }
+}
+
+/// Jaccard index of the lists treated as multisets
+pub struct Jaccard;
+
+impl Metric for Jaccard {
+    fn name(&self) -> &'static str {
+        "jaccard"
+    }
+
+    fn description(&self) -> &'static str {
+        "size of the multiset intersection divided by the size of the multiset union"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        let left_occurences = count_occurences(&lists.left);
+        let right_occurences = count_occurences(&lists.right);
+        let mut intersection = 0;
+        let mut union = 0;
+        for (value, left_count) in left_occurences.iter() {
+            let right_count = right_occurences.get(value).copied().unwrap_or_default();
+            intersection += left_count.min(&right_count);
+            union += left_count.max(&right_count);
+        }
+        union += right_occurences
+            .iter()
+            .filter(|(value, _)| !left_occurences.contains_key(value))
+            .map(|(_, count)| count)
+            .sum::<i64>();
+
+        // Two empty multisets are identical
+        if union == 0 {
+            return MetricValue::Real(1.0);
+        }
+        MetricValue::Real(intersection as f64 / union as f64)
+    }
+}
+
+/// Ranks of the values starting from 1, tied values get the average of their ranks
+fn average_ranks(values: &[i64]) -> Vec<f64> {
+    let mut order = (0..values.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| values[*idx]);
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let end = start + order[start..].partition_point(|idx| values[*idx] == values[order[start]]);
+        let rank = (start + end + 1) as f64 / 2.0;
+        for idx in order[start..end].iter() {
+            ranks[*idx] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+fn pearson(xs: &[f64], ys: &[f64]) -> f64 {
+    let count = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / count;
+    let mean_y = ys.iter().sum::<f64>() / count;
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    covariance / (variance_x * variance_y).sqrt()
+}
+
+/// Spearman's rank correlation between the values of the same row
+pub struct Spearman;
+
+impl Metric for Spearman {
+    fn name(&self) -> &'static str {
+        "spearman"
+    }
+
+    fn description(&self) -> &'static str {
+        "Spearman's rank correlation of the values in the same row"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        // Pearson correlation is NaN for constant lists, which matches the correlation being undefined
+        MetricValue::Real(pearson(&average_ranks(&lists.left), &average_ranks(&lists.right)))
+    }
+}
+
+/// Number of pairs among the values that are tied
+fn tied_pairs(sorted: impl Iterator<Item = impl PartialEq>) -> u64 {
+    let mut tied = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for value in sorted {
+        if previous.as_ref() == Some(&value) {
+            run += 1;
+        } else {
+            run = 1;
+        }
+        // Every new value in a run of equal values forms a pair with all the previous ones
+        tied += run - 1;
+        previous = Some(value);
+    }
+    tied
+}
+
+/// Sorts the values and returns the number of swaps an insertion sort would need
+fn count_swaps(values: &mut [i64]) -> u64 {
+    if values.len() < 2 {
+        return 0;
+    }
+    let middle = values.len() / 2;
+    let mut swaps = count_swaps(&mut values[..middle]) + count_swaps(&mut values[middle..]);
+
+    let mut merged = Vec::with_capacity(values.len());
+    let (mut left, mut right) = (0, middle);
+    while left < middle && right < values.len() {
+        if values[right] < values[left] {
+            // The value jumps over all the remaining values of the left half
+            swaps += (middle - left) as u64;
+            merged.push(values[right]);
+            right += 1;
+        } else {
+            merged.push(values[left]);
+            left += 1;
+        }
+    }
+    merged.extend_from_slice(&values[left..middle]);
+    merged.extend_from_slice(&values[right..]);
+    values.copy_from_slice(&merged);
+    swaps
+}
+
+/// Kendall's tau-b rank correlation between the values of the same row, calculated in `O(n log n)`
+pub struct Kendall;
+
+impl Metric for Kendall {
+    fn name(&self) -> &'static str {
+        "kendall"
+    }
+
+    fn description(&self) -> &'static str {
+        "Kendall's tau-b rank correlation of the values in the same row"
+    }
+
+    fn compute(&self, lists: &Lists) -> MetricValue {
+        let mut rows = lists
+            .left
+            .iter()
+            .copied()
+            .zip(lists.right.iter().copied())
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+
+        let count = rows.len() as u64;
+        let total_pairs = count * count.saturating_sub(1) / 2;
+        let left_ties = tied_pairs(rows.iter().map(|(left, _)| left));
+        let joint_ties = tied_pairs(rows.iter());
+        let mut right_values = rows.into_iter().map(|(_, right)| right).collect::<Vec<_>>();
+        // Pairs that are ordered differently by the left and the right values
+        let discordant = count_swaps(&mut right_values);
+        let right_ties = tied_pairs(right_values.iter());
+
+        let concordant_minus_discordant =
+            total_pairs as f64 - left_ties as f64 - right_ties as f64 + joint_ties as f64 - 2.0 * discordant as f64;
+        let normalization = ((total_pairs - left_ties) as f64 * (total_pairs - right_ties) as f64).sqrt();
+        MetricValue::Real(concordant_minus_discordant / normalization)
+    }
+}
+
+/// All the available metrics
+pub const METRICS: [&dyn Metric; 8] = [
+    &Distance,
+    &Similarity,
+    &SquaredDistance,
+    &MaxDistance,
+    &BidirectionalSimilarity,
+    &Jaccard,
+    &Spearman,
+    &Kendall,
+];
+
+pub fn find_metric(name: &str) -> Option<&'static dyn Metric> {
+    METRICS.into_iter().find(|metric| metric.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use crate::metric::{find_metric, Kendall, Lists, Metric, MetricValue, Spearman, METRICS};
+    use crate::{calculate_distance_and_similarity, count_occurences, ColumnFormat};
+
+    fn compute(name: &str, lists: &Lists) -> MetricValue {
+        find_metric(name).unwrap().compute(lists)
+    }
+
+    fn assert_close(value: MetricValue, expected: f64) {
+        let MetricValue::Real(value) = value else {
+            panic!("{:?} is not a real number", value);
+        };
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn calculates_sample_metrics() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+        let lists = Lists::read(input.as_bytes(), ColumnFormat::default()).unwrap();
+
+        assert_eq!(compute("distance", &lists), MetricValue::Integer(11));
+        assert_eq!(compute("similarity", &lists), MetricValue::Integer(31));
+        assert_eq!(compute("squared", &lists), MetricValue::Integer(35));
+        assert_eq!(compute("max", &lists), MetricValue::Integer(5));
+        assert_eq!(compute("bidirectional", &lists), MetricValue::Integer(62));
+        assert_close(compute("jaccard", &lists), 0.5);
+        assert_close(compute("spearman", &lists), -0.096_774_193_548_387_1);
+        assert_close(compute("kendall", &lists), -0.083_333_333_333_333_33);
+        assert!(find_metric("cosine").is_none());
+    }
+
+    #[test]
+    fn matches_batch_functions() {
+        let mut rng = Rng::new(15);
+        let left = (0..100).map(|_| rng.range(0..50)).collect::<Vec<_>>();
+        let right = (0..100).map(|_| rng.range(0..50)).collect::<Vec<_>>();
+        let lists = Lists::new(left, right);
+
+        let (distance, similarity) = calculate_distance_and_similarity(
+            &lists.sorted_left,
+            &lists.sorted_right,
+            Some(&count_occurences(&lists.sorted_right)),
+        );
+        assert_eq!(compute("distance", &lists), MetricValue::Integer(distance as i128));
+        assert_eq!(compute("similarity", &lists), MetricValue::Integer(similarity as i128));
+    }
+
+    #[test]
+    fn calculates_kendall_like_brute_force() {
+        let mut rng = Rng::new(15);
+        for _ in 0..50 {
+            let len = rng.index(30) + 2;
+            let left = (0..len).map(|_| rng.range(0..5)).collect::<Vec<_>>();
+            let right = (0..len).map(|_| rng.range(0..5)).collect::<Vec<_>>();
+
+            let (mut concordant, mut discordant, mut left_ties, mut right_ties) = (0f64, 0f64, 0f64, 0f64);
+            for first in 0..len {
+                for second in first + 1..len {
+                    let left_order = left[first].cmp(&left[second]);
+                    let right_order = right[first].cmp(&right[second]);
+                    match (left_order.is_eq(), right_order.is_eq()) {
+                        (true, true) => {}
+                        (true, false) => left_ties += 1.0,
+                        (false, true) => right_ties += 1.0,
+                        _ if left_order == right_order => concordant += 1.0,
+                        _ => discordant += 1.0,
+                    }
+                }
+            }
+            let expected = (concordant - discordant)
+                / ((concordant + discordant + left_ties) * (concordant + discordant + right_ties)).sqrt();
+
+            let value = Kendall.compute(&Lists::new(left, right));
+            match value {
+                MetricValue::Real(value) if expected.is_nan() => assert!(value.is_nan()),
+                value => assert_close(value, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn handles_degenerate_lists() {
+        let constant = Lists::new(vec![1, 1, 1], vec![1, 2, 3]);
+        assert!(matches!(Spearman.compute(&constant), MetricValue::Real(value) if value.is_nan()));
+        assert!(matches!(Kendall.compute(&constant), MetricValue::Real(value) if value.is_nan()));
+
+        for metric in METRICS {
+            // Must not panic on empty lists
+            metric.compute(&Lists::default());
+        }
+    }
+}
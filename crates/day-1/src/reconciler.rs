@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+/// One of the two reconciled lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn idx(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+        }
+    }
+
+    /// Change of the difference between the number of left and right values
+    fn step(self) -> i64 {
+        match self {
+            Side::Left => 1,
+            Side::Right => -1,
+        }
+    }
+}
+
+/// Smallest block size, so that small lists aren't split into tiny blocks
+const MIN_BLOCK_SIZE: usize = 32;
+
+/// A sorted chunk of values from both lists.
+///
+/// Walking through the values, the difference between the number of left and right values seen so far changes by one
+/// with every value. The block stores the gaps between its values grouped by that difference (relative to the start
+/// of the block), which allows summing `|difference| * gap` for any starting difference in constant time.
+#[derive(Debug, Default)]
+struct Block {
+    values: Vec<(i64, Side)>,
+    /// Difference after the whole block
+    walk: i64,
+    counts: [usize; 2],
+    sums: [i64; 2],
+    /// Smallest difference after a value of the block
+    min_walk: i64,
+    /// Prefix sums of the gaps, indexed by the difference minus `min_walk`
+    gap_prefix: Vec<i64>,
+    /// Prefix sums of the gaps multiplied by the difference
+    walk_gap_prefix: Vec<i64>,
+}
+
+impl Block {
+    fn new(values: Vec<(i64, Side)>) -> Self {
+        let mut block = Block {
+            values,
+            ..Default::default()
+        };
+        block.rebuild();
+        block
+    }
+
+    fn rebuild(&mut self) {
+        self.counts = [0; 2];
+        self.sums = [0; 2];
+        let mut walk = 0;
+        let mut walks = Vec::with_capacity(self.values.len());
+        for (value, side) in self.values.iter() {
+            self.counts[side.idx()] += 1;
+            self.sums[side.idx()] += value;
+            walk += side.step();
+            walks.push(walk);
+        }
+        self.walk = walk;
+        self.min_walk = walks.iter().copied().min().unwrap_or_default();
+        let max_walk = walks.iter().copied().max().unwrap_or_default();
+
+        let mut gaps = vec![0; (max_walk - self.min_walk + 1) as usize];
+        for (idx, pair) in self.values.windows(2).enumerate() {
+            gaps[(walks[idx] - self.min_walk) as usize] += pair[1].0 - pair[0].0;
+        }
+        self.gap_prefix = vec![0; gaps.len() + 1];
+        self.walk_gap_prefix = vec![0; gaps.len() + 1];
+        for (idx, gap) in gaps.into_iter().enumerate() {
+            self.gap_prefix[idx + 1] = self.gap_prefix[idx] + gap;
+            self.walk_gap_prefix[idx + 1] = self.walk_gap_prefix[idx] + gap * (idx as i64 + self.min_walk);
+        }
+    }
+
+    fn first(&self) -> i64 {
+        self.values[0].0
+    }
+
+    fn last(&self) -> i64 {
+        self.values[self.values.len() - 1].0
+    }
+
+    /// Sums `|difference| * gap` over the gaps inside of the block, given the difference before the block
+    fn weighted_gaps(&self, offset: i64) -> i64 {
+        // Differences that become non-negative once the offset is added
+        let split = (-offset - self.min_walk).clamp(0, self.gap_prefix.len() as i64 - 1) as usize;
+        let total = self.gap_prefix.len() - 1;
+        let negative = self.walk_gap_prefix[split] + offset * self.gap_prefix[split];
+        let positive = (self.walk_gap_prefix[total] - self.walk_gap_prefix[split])
+            + offset * (self.gap_prefix[total] - self.gap_prefix[split]);
+        positive - negative
+    }
+}
+
+/// Keeps the total distance and the similarity score of two lists up to date while their values are inserted and
+/// removed one at a time.
+///
+/// Values of both lists are kept sorted together in blocks of about `sqrt(n)` values, so every update and distance
+/// query takes `O(sqrt(n))` time, while the similarity score is updated in constant time.
+#[derive(Debug, Default)]
+pub struct ListReconciler {
+    blocks: Vec<Block>,
+    counts: [HashMap<i64, i64>; 2],
+    lens: [usize; 2],
+    similarity: i64,
+    /// Number of updates since the blocks were last rebalanced
+    updates: usize,
+}
+
+impl ListReconciler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_lists(left: &[i64], right: &[i64]) -> Self {
+        let mut reconciler = Self::new();
+        for (side, list) in [(Side::Left, left), (Side::Right, right)] {
+            for value in list {
+                reconciler.count(side, *value, 1);
+            }
+        }
+        let mut values = left
+            .iter()
+            .map(|value| (*value, Side::Left))
+            .chain(right.iter().map(|value| (*value, Side::Right)))
+            .collect::<Vec<_>>();
+        values.sort_unstable_by_key(|(value, _)| *value);
+        reconciler.rebalance(values);
+        reconciler
+    }
+
+    pub fn len(&self, side: Side) -> usize {
+        self.lens[side.idx()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lens == [0, 0]
+    }
+
+    pub fn insert(&mut self, side: Side, value: i64) {
+        self.count(side, value, 1);
+
+        let block_idx = self
+            .blocks
+            .partition_point(|block| block.last() < value)
+            .min(self.blocks.len().saturating_sub(1));
+        match self.blocks.get_mut(block_idx) {
+            Some(block) => {
+                let idx = block.values.partition_point(|(existing, _)| *existing < value);
+                block.values.insert(idx, (value, side));
+                block.rebuild();
+            }
+            None => self.blocks.push(Block::new(vec![(value, side)])),
+        }
+        self.after_update();
+    }
+
+    /// Removes a single occurence of the value, returns `false` if the list doesn't contain it
+    pub fn remove(&mut self, side: Side, value: i64) -> bool {
+        if self.counts[side.idx()].get(&value).is_none_or(|count| *count == 0) {
+            return false;
+        }
+        self.count(side, value, -1);
+
+        // Equal values can span multiple blocks
+        let first_block = self.blocks.partition_point(|block| block.last() < value);
+        for block_idx in first_block..self.blocks.len() {
+            let block = &mut self.blocks[block_idx];
+            if let Some(idx) = block.values.iter().position(|entry| *entry == (value, side)) {
+                block.values.remove(idx);
+                if block.values.is_empty() {
+                    self.blocks.remove(block_idx);
+                } else {
+                    block.rebuild();
+                }
+                break;
+            }
+        }
+        self.after_update();
+        true
+    }
+
+    pub fn similarity(&self) -> i64 {
+        self.similarity
+    }
+
+    /// Calculates the total distance between the lists.
+    ///
+    /// If the lists have different lengths, only the smallest values of the longer list are paired.
+    pub fn distance(&self) -> i64 {
+        let [left_len, right_len] = self.lens;
+        let paired = left_len.min(right_len);
+        if paired == 0 {
+            return 0;
+        }
+        // Values of the longer list past the last paired one are ignored
+        let (limit, shorter) = if left_len > right_len {
+            (Some(self.nth(Side::Left, paired - 1)), Side::Right)
+        } else if right_len > left_len {
+            (Some(self.nth(Side::Right, paired - 1)), Side::Left)
+        } else {
+            (None, Side::Left)
+        };
+        let clip = |from: i64, to: i64| match limit {
+            Some(limit) => (to.min(limit) - from).max(0),
+            None => to - from,
+        };
+
+        // Sum of `|left values so far - right values so far|` over the gaps between consecutive values
+        let mut distance = 0;
+        let mut offset: i64 = 0;
+        let mut previous = None;
+        for block in self.blocks.iter() {
+            if let Some(previous) = previous {
+                distance += offset.abs() * clip(previous, block.first());
+            }
+            if limit.is_none_or(|limit| block.last() <= limit) {
+                distance += block.weighted_gaps(offset);
+            } else {
+                let mut walk = offset;
+                for pair in block.values.windows(2) {
+                    walk += pair[0].1.step();
+                    distance += walk.abs() * clip(pair[0].0, pair[1].0);
+                }
+            }
+            offset += block.walk;
+            previous = Some(block.last());
+        }
+
+        // Values of the shorter list above the limit are paired with values that are at most the limit
+        if let Some(limit) = limit {
+            for block in self.blocks.iter().filter(|block| block.last() > limit) {
+                if block.first() > limit {
+                    let idx = shorter.idx();
+                    distance += block.sums[idx] - block.counts[idx] as i64 * limit;
+                } else {
+                    distance += block
+                        .values
+                        .iter()
+                        .filter(|(value, side)| *side == shorter && *value > limit)
+                        .map(|(value, _)| value - limit)
+                        .sum::<i64>();
+                }
+            }
+        }
+
+        distance
+    }
+
+    /// Returns the n-th smallest value of the list
+    fn nth(&self, side: Side, mut n: usize) -> i64 {
+        for block in self.blocks.iter() {
+            let count = block.counts[side.idx()];
+            if n < count {
+                return block
+                    .values
+                    .iter()
+                    .filter(|(_, value_side)| *value_side == side)
+                    .nth(n)
+                    .expect("block has enough values")
+                    .0;
+            }
+            n -= count;
+        }
+        panic!("list has fewer values than requested");
+    }
+
+    fn count(&mut self, side: Side, value: i64, change: i64) {
+        let count = self.counts[side.idx()].entry(value).or_default();
+        *count += change;
+        if *count == 0 {
+            self.counts[side.idx()].remove(&value);
+        }
+        if change > 0 {
+            self.lens[side.idx()] += 1;
+        } else {
+            self.lens[side.idx()] -= 1;
+        }
+
+        let other = match side {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        };
+        let other_count = self.counts[other.idx()].get(&value).copied().unwrap_or_default();
+        self.similarity += change * value * other_count;
+    }
+
+    fn block_size(&self) -> usize {
+        (((self.lens[0] + self.lens[1]) as f64).sqrt() as usize).max(MIN_BLOCK_SIZE)
+    }
+
+    /// Rebalances the blocks once enough updates were made to make them uneven
+    fn after_update(&mut self) {
+        self.updates += 1;
+        if self.updates >= self.block_size() {
+            let values = self.blocks.drain(..).flat_map(|block| block.values).collect();
+            self.rebalance(values);
+        }
+    }
+
+    fn rebalance(&mut self, values: Vec<(i64, Side)>) {
+        let block_size = self.block_size();
+        self.blocks = values
+            .chunks(block_size)
+            .map(|chunk| Block::new(chunk.to_vec()))
+            .collect();
+        self.updates = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use crate::reconciler::{ListReconciler, Side};
+    use crate::{calculate_distance_and_similarity, count_occurences, read_input_into_lists};
+
+    #[test]
+    fn matches_sample_results() {
+        let (list_1, list_2) =
+            read_input_into_lists("3   4\n4   3\n2   5\n1   3\n3   9\n3   3".as_bytes(), None).unwrap();
+
+        let mut reconciler = ListReconciler::from_lists(&list_1, &list_2);
+        assert_eq!((reconciler.distance(), reconciler.similarity()), (11, 31));
+
+        assert!(reconciler.remove(Side::Right, 9));
+        assert!(!reconciler.remove(Side::Right, 9));
+        reconciler.insert(Side::Right, 4);
+        assert_eq!((reconciler.distance(), reconciler.similarity()), (6, 35));
+    }
+
+    #[test]
+    fn matches_batch_results_after_every_update() {
+        let mut rng = Rng::new(13);
+        for _ in 0..20 {
+            let mut reconciler = ListReconciler::new();
+            let mut lists = [Vec::new(), Vec::new()];
+            // Small value range to get plenty of duplicates
+            let max_value = rng.range(1..200);
+            for _ in 0..400 {
+                let side = *rng.choose(&[Side::Left, Side::Right]);
+                let list = &mut lists[side as usize];
+                if !list.is_empty() && rng.chance(0.3) {
+                    let value = list.swap_remove(rng.index(list.len()));
+                    assert!(reconciler.remove(side, value));
+                } else {
+                    let value = rng.range(-max_value..max_value);
+                    list.push(value);
+                    reconciler.insert(side, value);
+                }
+
+                let mut sorted = lists.clone();
+                sorted.iter_mut().for_each(|list| list.sort());
+                let paired = sorted[0].len().min(sorted[1].len());
+                let (distance, _) = calculate_distance_and_similarity(&sorted[0][..paired], &sorted[1][..paired], None);
+                let right_occurences = count_occurences(&sorted[1]);
+                let similarity = sorted[0]
+                    .iter()
+                    .map(|value| value * right_occurences.get(value).copied().unwrap_or_default())
+                    .sum::<i64>();
+
+                assert_eq!(reconciler.distance(), distance);
+                assert_eq!(reconciler.similarity(), similarity);
+                assert_eq!(reconciler.len(Side::Left), lists[0].len());
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use common::{Json, ToJson};
+
+use crate::count_occurences;
+
+/// A single pair of values with the same rank in both sorted lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairRow {
+    /// 1-based rank of the values
+    pub rank: usize,
+    pub left: i64,
+    pub right: i64,
+    pub distance: i64,
+    /// Contribution of the left value to the similarity score
+    pub similarity: i64,
+}
+
+/// A value that appears a different number of times in each list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountMismatch {
+    pub value: i64,
+    pub left_count: usize,
+    pub right_count: usize,
+}
+
+/// Multiset difference between the lists, every group is sorted by value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultisetDiff {
+    /// Values that don't appear in the right list
+    pub only_left: Vec<CountMismatch>,
+    /// Values that don't appear in the left list
+    pub only_right: Vec<CountMismatch>,
+    /// Values that appear in both lists, but a different number of times
+    pub count_mismatches: Vec<CountMismatch>,
+}
+
+/// Breakdown of the total distance and the similarity score by the pairs that contribute to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    /// Only the values that have a counterpart in the other list are paired
+    pub pairs: Vec<PairRow>,
+    pub diff: MultisetDiff,
+    pub distance: i64,
+    pub similarity: i64,
+}
+
+impl ReconciliationReport {
+    /// Creates the report for two sorted lists
+    pub fn new(list_1: &[i64], list_2: &[i64]) -> Self {
+        let right_occurences = count_occurences(list_2);
+        let pairs = list_1
+            .iter()
+            .zip(list_2)
+            .enumerate()
+            .map(|(idx, (left, right))| PairRow {
+                rank: idx + 1,
+                left: *left,
+                right: *right,
+                distance: (left - right).abs(),
+                similarity: left * right_occurences.get(left).copied().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        // Unpaired left values still contribute to the similarity score
+        let similarity = list_1
+            .iter()
+            .map(|left| left * right_occurences.get(left).copied().unwrap_or_default())
+            .sum();
+
+        let mut counts = BTreeMap::<i64, [usize; 2]>::new();
+        for (idx, list) in [list_1, list_2].into_iter().enumerate() {
+            for value in list {
+                counts.entry(*value).or_default()[idx] += 1;
+            }
+        }
+        let mut diff = MultisetDiff::default();
+        for (value, [left_count, right_count]) in counts {
+            let mismatch = CountMismatch {
+                value,
+                left_count,
+                right_count,
+            };
+            match (left_count, right_count) {
+                (_, 0) => diff.only_left.push(mismatch),
+                (0, _) => diff.only_right.push(mismatch),
+                (left_count, right_count) if left_count != right_count => diff.count_mismatches.push(mismatch),
+                _ => {}
+            }
+        }
+
+        ReconciliationReport {
+            distance: pairs.iter().map(|pair| pair.distance).sum(),
+            similarity,
+            pairs,
+            diff,
+        }
+    }
+
+    /// Formats the pairs as CSV with a header row
+    pub fn pairs_csv(&self) -> String {
+        let mut csv = "rank,left,right,distance,similarity\n".to_string();
+        for pair in self.pairs.iter() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}",
+                pair.rank, pair.left, pair.right, pair.distance, pair.similarity
+            );
+        }
+        csv
+    }
+
+    /// Formats the multiset difference as CSV with a header row
+    pub fn diff_csv(&self) -> String {
+        let mut csv = "kind,value,left_count,right_count\n".to_string();
+        for (kind, mismatches) in self.diff_groups() {
+            for mismatch in mismatches {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{}",
+                    kind, mismatch.value, mismatch.left_count, mismatch.right_count
+                );
+            }
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> Json {
+        let pairs = self.pairs.iter().map(|pair| {
+            Json::object([
+                ("rank", pair.rank.to_json()),
+                ("left", pair.left.to_json()),
+                ("right", pair.right.to_json()),
+                ("distance", pair.distance.to_json()),
+                ("similarity", pair.similarity.to_json()),
+            ])
+        });
+        let diff = self.diff_groups().map(|(kind, mismatches)| {
+            let mismatches = mismatches.iter().map(|mismatch| {
+                Json::object([
+                    ("value", mismatch.value.to_json()),
+                    ("left_count", mismatch.left_count.to_json()),
+                    ("right_count", mismatch.right_count.to_json()),
+                ])
+            });
+            (kind, Json::Array(mismatches.collect()))
+        });
+
+        Json::object([
+            ("distance", self.distance.to_json()),
+            ("similarity", self.similarity.to_json()),
+            ("pairs", Json::Array(pairs.collect())),
+            ("diff", Json::object(diff)),
+        ])
+    }
+
+    fn diff_groups(&self) -> [(&'static str, &[CountMismatch]); 3] {
+        [
+            ("only_left", &self.diff.only_left),
+            ("only_right", &self.diff.only_right),
+            ("count_mismatch", &self.diff.count_mismatches),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{CountMismatch, PairRow, ReconciliationReport};
+    use crate::{calculate_distance_and_similarity, read_input_into_lists};
+
+    #[test]
+    fn breaks_down_sample_results() {
+        let mut occurence_map = Default::default();
+        let (list_1, list_2) = read_input_into_lists(
+            "3   4\n4   3\n2   5\n1   3\n3   9\n3   3".as_bytes(),
+            Some(&mut occurence_map),
+        )
+        .unwrap();
+
+        let report = ReconciliationReport::new(&list_1, &list_2);
+        assert_eq!(
+            (report.distance, report.similarity),
+            calculate_distance_and_similarity(&list_1, &list_2, Some(&occurence_map))
+        );
+        assert_eq!(
+            report.pairs[0],
+            PairRow {
+                rank: 1,
+                left: 1,
+                right: 3,
+                distance: 2,
+                similarity: 0,
+            }
+        );
+        assert_eq!(report.pairs.iter().map(|pair| pair.similarity).sum::<i64>(), 31);
+
+        let mismatch = |value, left_count, right_count| CountMismatch {
+            value,
+            left_count,
+            right_count,
+        };
+        assert_eq!(report.diff.only_left, [mismatch(1, 1, 0), mismatch(2, 1, 0)]);
+        assert_eq!(report.diff.only_right, [mismatch(5, 0, 1), mismatch(9, 0, 1)]);
+        assert_eq!(report.diff.count_mismatches, []);
+    }
+
+    #[test]
+    fn exports_csv_and_json() {
+        let report = ReconciliationReport::new(&[1, 2, 2], &[2, 3, 3]);
+
+        assert_eq!(
+            report.pairs_csv(),
+            "rank,left,right,distance,similarity\n1,1,2,1,0\n2,2,3,1,2\n3,2,3,1,2\n"
+        );
+        assert_eq!(
+            report.diff_csv(),
+            "kind,value,left_count,right_count\nonly_left,1,1,0\nonly_right,3,0,2\ncount_mismatch,2,2,1\n"
+        );
+        assert_eq!(
+            report.to_json().to_string(),
+            concat!(
+                r#"{"distance":3,"similarity":4,"pairs":["#,
+                r#"{"rank":1,"left":1,"right":2,"distance":1,"similarity":0},"#,
+                r#"{"rank":2,"left":2,"right":3,"distance":1,"similarity":2},"#,
+                r#"{"rank":3,"left":2,"right":3,"distance":1,"similarity":2}],"#,
+                r#""diff":{"only_left":[{"value":1,"left_count":1,"right_count":0}],"#,
+                r#""only_right":[{"value":3,"left_count":0,"right_count":2}],"#,
+                r#""count_mismatch":[{"value":2,"left_count":2,"right_count":1}]}}"#
+            )
+        );
+    }
+}
@@ -1,13 +1,18 @@
 use std::collections::HashMap;
 use std::mem;
 
+use common::{parse_token, InputError, ParseError, Solution};
+
 type FrequencyMap = HashMap<u128, u128>;
 
-pub fn parse_input(src: &str) -> FrequencyMap {
-    src.split(" ").fold(HashMap::new(), |mut acc, num| {
-        let num = num.parse::<u128>().expect("not a valid integer");
+pub fn parse_input(src: &str) -> Result<FrequencyMap, ParseError> {
+    // The stones are listed on a single line, which may be terminated by a line break
+    let src = src.strip_suffix('\n').unwrap_or(src);
+
+    src.split(" ").try_fold(HashMap::new(), |mut acc, num| {
+        let num = parse_token::<u128>(0, src, num, "a stone number")?;
         *acc.entry(num).or_insert(0) += 1;
-        acc
+        Ok(acc)
     })
 }
 
@@ -32,6 +37,15 @@ pub fn solve_both_parts(mut map: FrequencyMap) -> (u128, u128) {
     (part_1, part_2)
 }
 
+/// Counts the stones after blinking the given number of times
+pub fn count_stones(mut map: FrequencyMap, blinks: usize) -> u128 {
+    let mut temp_map = HashMap::new();
+    for _ in 0..blinks {
+        blink(&mut map, &mut temp_map);
+    }
+    map.values().sum()
+}
+
 fn blink(map: &mut FrequencyMap, temp: &mut FrequencyMap) {
     for (item, occurences) in map.drain() {
         if occurences == 0 {
@@ -41,7 +55,7 @@ fn blink(map: &mut FrequencyMap, temp: &mut FrequencyMap) {
 
         if item == 0 {
             *temp.entry(1).or_insert(0) += occurences;
-        } else if num_of_digits % 2 == 0 {
+        } else if num_of_digits.is_multiple_of(2) {
             let mut left_number = item;
             let mut right_number = 0;
             for idx in 0..num_of_digits {
@@ -63,16 +77,55 @@ fn blink(map: &mut FrequencyMap, temp: &mut FrequencyMap) {
     mem::swap(map, temp);
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = FrequencyMap;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_stones(input.clone(), 25)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        count_stones(input.clone(), 75)
+    }
+
+    fn solve(input: &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        solve_both_parts(input.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{parse_input, solve_both_parts};
+    use common::ParseError;
+
+    use crate::{count_stones, parse_input, solve_both_parts};
 
     #[test]
     fn correctly_solves_both_parts() {
-        let map = parse_input("125 17");
+        let map = parse_input("125 17").unwrap();
 
-        let (part_1, part_2) = solve_both_parts(map);
+        let (part_1, part_2) = solve_both_parts(map.clone());
         assert_eq!(part_1, 55312);
         assert_eq!(part_2, 65601038650482);
+
+        assert_eq!(count_stones(map.clone(), 25), part_1);
+        assert_eq!(count_stones(map, 75), part_2);
+    }
+
+    #[test]
+    fn reports_trailing_space() {
+        assert_eq!(
+            parse_input("125 17 \n").err(),
+            Some(ParseError::new(1, 8, "", "a stone number"))
+        );
     }
 }
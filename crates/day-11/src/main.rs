@@ -1,15 +1,10 @@
-use std::{env, fs};
+use std::process::ExitCode;
 
-use day_11::{parse_input, solve_both_parts};
+use day_11::Day11;
 
-fn main() {
-    let input_path = env::args().nth(1).expect("input file path is missing");
-    let input = fs::read_to_string(input_path).expect("error while reading the input");
-
-    let map = parse_input(&input);
-
-    let (part_1, part_2) = solve_both_parts(map);
-
-    println!("Number of stones after 25 bliks: {}", part_1);
-    println!("Number of stones after 75 bliks: {}", part_2);
+fn main() -> ExitCode {
+    common::run::<Day11>([
+        "Number of stones after 25 bliks: {}",
+        "Number of stones after 75 bliks: {}",
+    ])
 }
@@ -0,0 +1,142 @@
+use std::collections::{HashSet, VecDeque};
+
+use common::{InputError, ParseError, Solution};
+use grid::{Direction, Grid, Point};
+
+type Region = HashSet<Point>;
+
+fn calculate_perimeter_for_region(region: &Region) -> usize {
+    let mut perimeter = 0;
+    for cell in region {
+        let neighbours_in_region = Direction::CARDINAL
+            .into_iter()
+            .filter(|direction| region.contains(&cell.step(*direction)))
+            .count();
+        perimeter += 4 - neighbours_in_region;
+    }
+
+    perimeter
+}
+
+fn calculate_corners_for_region(region: &Region) -> usize {
+    let mut corners = 0;
+    for cell in region.iter().copied() {
+        let mut cell_corners = 0;
+
+        // Check the corner between each pair of adjacent sides of the cell
+        for direction in Direction::CARDINAL {
+            let next_direction = direction.turn_right();
+            let corner_idxs = [
+                region.contains(&cell.step(direction)),
+                region.contains(&cell.step(direction).step(next_direction)),
+                region.contains(&cell.step(next_direction)),
+            ];
+            if corner_idxs == [true, false, true]
+                || corner_idxs == [false, false, false]
+                || corner_idxs == [false, true, false]
+            {
+                cell_corners += 1;
+            }
+        }
+
+        corners += cell_corners
+    }
+
+    corners
+}
+
+pub fn parse_input(src: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::try_parse(src, "a plant type (an uppercase letter)", |crop| {
+        crop.is_ascii_uppercase().then_some(crop)
+    })
+}
+
+fn find_regions(grid: &Grid<u8>) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut seen = Grid::new(grid.width(), grid.height(), false);
+    for (position, crop) in grid.iter() {
+        if seen[position] {
+            continue;
+        }
+
+        let mut region = Region::new();
+        let mut region_queue = VecDeque::from(vec![position]);
+        while let Some(position) = region_queue.pop_front() {
+            if &grid[position] != crop || seen[position] {
+                continue;
+            }
+
+            region_queue.extend(
+                grid.neighbours(position, &Direction::CARDINAL)
+                    .map(|(neighbour, _)| neighbour),
+            );
+
+            seen[position] = true;
+            region.insert(position);
+        }
+
+        regions.push(region);
+    }
+
+    regions
+}
+
+/// Sums the prices of fencing the regions, where the price is the area multiplied by `measure` of the region
+fn total_price(regions: &[Region], measure: fn(&Region) -> usize) -> usize {
+    regions.iter().map(|region| region.len() * measure(region)).sum()
+}
+
+pub fn solve_both_parts(grid: &Grid<u8>) -> (usize, usize) {
+    let regions = find_regions(grid);
+
+    let part_1 = total_price(&regions, calculate_perimeter_for_region);
+    let part_2 = total_price(&regions, calculate_corners_for_region);
+
+    (part_1, part_2)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        total_price(&find_regions(input), calculate_perimeter_for_region)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        total_price(&find_regions(input), calculate_corners_for_region)
+    }
+
+    fn solve(input: &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        solve_both_parts(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Solution;
+
+    use crate::{parse_input, solve_both_parts, Day12};
+
+    #[test]
+    fn correctly_solves_both_parts() {
+        let input = "RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\nVVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE";
+        let grid = parse_input(input).unwrap();
+
+        let (part_1, part_2) = solve_both_parts(&grid);
+        assert_eq!(part_1, 1930);
+        assert_eq!(part_2, 1206);
+
+        assert_eq!(Day12::part_1(&grid), part_1);
+        assert_eq!(Day12::part_2(&grid), part_2);
+    }
+}
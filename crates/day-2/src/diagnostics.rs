@@ -0,0 +1,145 @@
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+use common::{InputError, Json, ToJson};
+
+use crate::{parse_report, SafetyPolicy, Violation};
+
+/// Explanation of why a report isn't safe as is.
+///
+/// Lines and levels are counted from 1 only when the diagnostic is rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Index of the report's line
+    pub line_idx: usize,
+    pub violation: Violation,
+    /// Index of the first level that breaks the rules
+    pub level_idx: usize,
+    /// The offending pair of adjacent levels
+    pub levels: (i64, i64),
+    /// Indices of the levels whose removal makes the report safe, `None` if it takes more than `max_removals`
+    pub removed: Option<Vec<usize>>,
+}
+
+impl Diagnostic {
+    /// Explains why a report is unsafe, returns `None` for safe reports
+    pub fn new(line_idx: usize, levels: &[i64], policy: &SafetyPolicy) -> Option<Self> {
+        let (level_idx, violation) = policy.find_violation(levels)?;
+
+        Some(Diagnostic {
+            line_idx,
+            violation,
+            level_idx,
+            levels: (levels[level_idx - 1], levels[level_idx]),
+            removed: policy.levels_to_remove(levels),
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} at level {} ({} -> {})",
+            self.line_idx + 1,
+            self.violation,
+            self.level_idx + 1,
+            self.levels.0,
+            self.levels.1
+        )?;
+        match &self.removed {
+            Some(removed) => {
+                let noun = if removed.len() == 1 { "level" } else { "levels" };
+                let removed = removed.iter().map(|idx| (idx + 1).to_string()).collect::<Vec<_>>();
+                write!(f, ", safe after removing {} {}", noun, removed.join(", "))
+            }
+            None => write!(f, ", unsafe"),
+        }
+    }
+}
+
+impl ToJson for Diagnostic {
+    fn to_json(&self) -> Json {
+        let removed = match &self.removed {
+            Some(removed) => Json::Array(removed.iter().map(|idx| (idx + 1).to_json()).collect()),
+            None => Json::Null,
+        };
+        Json::object([
+            ("line", (self.line_idx + 1).to_json()),
+            ("violation", Json::String(self.violation.name().to_string())),
+            ("position", (self.level_idx + 1).to_json()),
+            (
+                "levels",
+                Json::Array(vec![self.levels.0.to_json(), self.levels.1.to_json()]),
+            ),
+            ("removed", removed),
+        ])
+    }
+}
+
+/// Explains every report of the input that isn't safe as is
+pub fn explain_reports<R: Read>(src: R, policy: &SafetyPolicy) -> Result<Vec<Diagnostic>, InputError> {
+    let reader = BufReader::new(src);
+
+    let mut diagnostics = Vec::new();
+    for (line_idx, line) in reader.lines().enumerate() {
+        let levels = parse_report(line_idx, &line?)?;
+        diagnostics.extend(Diagnostic::new(line_idx, &levels, policy));
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::ToJson;
+
+    use crate::{explain_reports, Diagnostic, SafetyPolicy, Violation};
+
+    const TEST_DATA: &str = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9";
+
+    #[test]
+    fn explains_unsafe_reports() {
+        let diagnostics = explain_reports(TEST_DATA.as_bytes(), &SafetyPolicy::default()).unwrap();
+
+        let summary = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line_idx, diagnostic.violation, diagnostic.removed.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (1, Violation::StepTooLarge, None),
+                (2, Violation::StepTooLarge, None),
+                (3, Violation::DirectionChange, Some(vec![1])),
+                (4, Violation::ZeroStep, Some(vec![2])),
+            ]
+        );
+        assert_eq!(diagnostics[2].levels, (3, 2));
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "line 4: levels changed direction at level 3 (3 -> 2), safe after removing level 2"
+        );
+    }
+
+    #[test]
+    fn lists_every_removed_level() {
+        let policy = SafetyPolicy {
+            max_removals: 2,
+            ..SafetyPolicy::default()
+        };
+        let diagnostic = Diagnostic::new(0, &[5, 1, 2, 9, 3, 4], &policy).unwrap();
+
+        assert_eq!(diagnostic.removed, Some(vec![0, 3]));
+        assert_eq!(
+            diagnostic.to_json().to_string(),
+            r#"{"line":1,"violation":"step-too-large","position":2,"levels":[5,1],"removed":[1,4]}"#
+        );
+
+        let policy = SafetyPolicy {
+            max_removals: 1,
+            ..policy
+        };
+        assert_eq!(Diagnostic::new(0, &[5, 1, 2, 9, 3, 4], &policy).unwrap().removed, None);
+    }
+}
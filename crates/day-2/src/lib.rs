@@ -1,109 +1,158 @@
-use std::cmp::Ordering;
+mod diagnostics;
+mod parallel;
+mod policy;
+
 use std::io::{BufRead, BufReader, Read};
 
-enum LinePattern {
-    Unset,
-    Decreasing,
-    Increasing,
+use common::{parse_token, InputError, ParseError, Solution};
+
+pub use diagnostics::{explain_reports, Diagnostic};
+pub use parallel::{count_safe_reports_parallel, find_safe_reports_parallel};
+pub use policy::{min_removals, Monotonicity, SafetyPolicy, Violation};
+
+/// Parses the space-separated levels of a report
+pub fn parse_report(line_idx: usize, line: &str) -> Result<Vec<i64>, ParseError> {
+    line.split(' ')
+        .map(|level| parse_token(line_idx, line, level, "an integer"))
+        .collect()
 }
 
-fn is_report_safe(line: &str, skip_idx: Option<usize>) -> (bool, usize) {
-    let mut last_number = None;
-    let mut pattern = LinePattern::Unset;
-    for (idx, num) in line
-        .split(" ")
-        .enumerate()
-        .filter(|&(idx, _)| skip_idx.is_none() || idx != skip_idx.unwrap())
-    {
-        let num: i64 = num.parse().expect("not a valid integer found in the input");
-
-        if let Some(last_num) = last_number {
-            match pattern {
-                LinePattern::Unset => {
-                    pattern = match num.cmp(&last_num) {
-                        std::cmp::Ordering::Less => LinePattern::Decreasing,
-                        std::cmp::Ordering::Greater => LinePattern::Increasing,
-                        std::cmp::Ordering::Equal => {
-                            // Not a safe report
-                            return (false, idx);
-                        }
-                    };
-                }
-                LinePattern::Decreasing => {
-                    if num.cmp(&last_num) != Ordering::Less {
-                        return (false, idx);
-                    }
-                }
-                LinePattern::Increasing => {
-                    if num.cmp(&last_num) != Ordering::Greater {
-                        return (false, idx);
-                    }
-                }
-            }
-
-            let number_diff = (num - last_num).abs();
-
-            if !(1..=3).contains(&number_diff) {
-                return (false, idx);
-            }
-        }
+/// Counts a report in every `counts[k]` that allows at least the given number of removed levels
+fn tally(counts: &mut [usize], removals_needed: Option<usize>) {
+    if let Some(removals) = removals_needed {
+        counts[removals..].iter_mut().for_each(|count| *count += 1);
+    }
+}
+
+/// Counts the reports that are safe under `policy`.
+///
+/// The `k`-th count includes every report that needs at most `k` removed levels, up to `policy.max_removals`.
+pub fn count_safe_reports<R: Read>(src: R, policy: &SafetyPolicy) -> Result<Vec<usize>, InputError> {
+    let reader = BufReader::new(src);
 
-        last_number = Some(num);
+    let mut counts = vec![0; policy.max_removals + 1];
+    for (line_idx, line) in reader.lines().enumerate() {
+        let levels = parse_report(line_idx, &line?)?;
+        tally(&mut counts, policy.removals_needed(&levels));
     }
 
-    (true, 0)
+    Ok(counts)
 }
 
-pub fn find_safe_reports<R: Read>(src: R) -> (i32, i32) {
+/// Counts the reports by the fewest levels that have to be removed to make them safe, `histogram[k]` has the reports
+/// that need exactly `k` removals
+pub fn removals_histogram<R: Read>(src: R, policy: &SafetyPolicy) -> Result<Vec<usize>, InputError> {
     let reader = BufReader::new(src);
 
-    let mut safe_reports_part_1 = 0;
-    let mut safe_reports_part_2 = 0;
-    'outer: for report in reader.lines() {
-        let line = report.expect("error while reading a line from the input file");
+    let mut histogram = Vec::new();
+    for (line_idx, line) in reader.lines().enumerate() {
+        let removals = min_removals(&parse_report(line_idx, &line?)?, policy);
+        if histogram.len() <= removals {
+            histogram.resize(removals + 1, 0);
+        }
+        histogram[removals] += 1;
+    }
 
-        let mut tolerated_a_level = false;
-        let mut indexes_to_skip = Vec::new();
-        loop {
-            let index = indexes_to_skip.pop();
+    Ok(histogram)
+}
 
-            if tolerated_a_level && index.is_none() {
-                // Tolerating a bad level didn't help, report is still unsafe
-                continue 'outer;
-            }
+/// Counts the safe reports according to the rules of both parts of the puzzle
+pub fn find_safe_reports<R: Read>(src: R) -> Result<(usize, usize), InputError> {
+    let counts = count_safe_reports(src, &SafetyPolicy::default())?;
+    Ok((counts[0], counts[1]))
+}
 
-            let (safe, failing_idx) = is_report_safe(&line, index);
+pub struct Day2;
 
-            if !safe && !tolerated_a_level {
-                tolerated_a_level = true;
-                indexes_to_skip.extend(0..=failing_idx);
-                continue;
-            }
+impl Solution for Day2 {
+    const DAY: u8 = 2;
 
-            if safe {
-                break;
-            }
-        }
-        if !tolerated_a_level {
-            safe_reports_part_1 += 1;
-        }
-        safe_reports_part_2 += 1;
+    type Input = Vec<Vec<i64>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        let reports = input
+            .lines()
+            .enumerate()
+            .map(|(line_idx, line)| parse_report(line_idx, line))
+            .collect::<Result<_, _>>()?;
+        Ok(reports)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        let policy = SafetyPolicy {
+            max_removals: 0,
+            ..Default::default()
+        };
+        input
+            .iter()
+            .filter(|levels| policy.removals_needed(levels).is_some())
+            .count()
     }
 
-    (safe_reports_part_1, safe_reports_part_2)
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        let policy = SafetyPolicy::default();
+        input
+            .iter()
+            .filter(|levels| policy.removals_needed(levels).is_some())
+            .count()
+    }
+
+    fn solve(input: &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        let policy = SafetyPolicy::default();
+        let mut counts = [0; 2];
+        for levels in input {
+            tally(&mut counts, policy.removals_needed(levels));
+        }
+        (counts[0], counts[1])
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::find_safe_reports;
+    use common::{InputError, ParseError, Solution};
+
+    use crate::{count_safe_reports, find_safe_reports, removals_histogram, Day2, Monotonicity, SafetyPolicy};
 
     const TEST_DATA: &str = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9";
 
     #[test]
     fn correctly_determines_safe_reports() {
-        let (part_1, part_2) = find_safe_reports(TEST_DATA.as_bytes());
+        let (part_1, part_2) = find_safe_reports(TEST_DATA.as_bytes()).unwrap();
 
         assert_eq!(part_1, 2);
         assert_eq!(part_2, 4);
+
+        let reports = Day2::parse(TEST_DATA).unwrap();
+        assert_eq!(Day2::part_1(&reports), 2);
+        assert_eq!(Day2::part_2(&reports), 4);
+    }
+
+    #[test]
+    fn counts_safe_reports_for_each_removal_limit() {
+        let policy = SafetyPolicy {
+            steps: 0..=4,
+            monotonicity: Monotonicity::NonStrict,
+            max_removals: 2,
+        };
+
+        assert_eq!(count_safe_reports(TEST_DATA.as_bytes(), &policy).unwrap(), [4, 5, 6]);
+    }
+
+    #[test]
+    fn builds_removals_histogram() {
+        let histogram = removals_histogram(TEST_DATA.as_bytes(), &SafetyPolicy::default()).unwrap();
+
+        assert_eq!(histogram, [2, 2, 2]);
+    }
+
+    #[test]
+    fn reports_position_of_invalid_level() {
+        let Err(InputError::Parse(error)) = find_safe_reports("7 6 4 2 1\n1 2  7 8 9".as_bytes()) else {
+            panic!("input should be rejected");
+        };
+
+        assert_eq!(error, ParseError::new(2, 5, "", "an integer"));
     }
 }
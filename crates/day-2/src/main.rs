@@ -1,14 +1,185 @@
 use std::env;
-use std::fs::File;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-use day_2::find_safe_reports;
+use common::{default_inputs_dir, input_name, input_path, read_input, Format, Json, ToJson};
+use day_2::{count_safe_reports, count_safe_reports_parallel, explain_reports, removals_histogram, Day2, SafetyPolicy};
 
-fn main() {
-    let input_path = env::args().nth(1).expect("input file path is missing");
-    let input_file = File::open(&input_path).expect("error while reading the file");
+const USAGE: &str = "Usage:
+    day-2 [--format <text|json>] [INPUT]...
+    day-2 [--threads <N>] [POLICY OPTIONS] [INPUT]
+    day-2 --histogram [POLICY OPTIONS] [INPUT]
+    day-2 --explain [--format <text|json>] [POLICY OPTIONS] [INPUT]
 
-    let (part_1, part_2) = find_safe_reports(&input_file);
+Options:
+    --threads <N>             Count the safe reports in batches of lines on N threads
+    --histogram               Count the reports by the fewest levels that have to be removed to make them safe
+    --explain                 Explain why every unsafe report breaks the rules and which levels make it safe
+    --format <FORMAT>         Output format of the explanations: 'text' or 'json' [default: text]
 
-    println!("Total number of safe reports for part 1: {}", part_1);
-    println!("Total number of safe reports for part 2: {}", part_2);
+Policy options:
+    --min-step <N>            Smallest allowed difference between adjacent levels [default: 1]
+    --max-step <N>            Largest allowed difference between adjacent levels [default: 3]
+    --monotonicity <KIND>     'strict', 'non-strict' or 'either' [default: strict]
+    --max-removals <K>        Count the safe reports for up to K removed levels, at most 1000 [default: 1]";
+
+/// Templates of the answers in the text format
+const TEMPLATES: [&str; 2] = [
+    "Total number of safe reports for part 1: {}",
+    "Total number of safe reports for part 2: {}",
+];
+
+/// Largest accepted `--max-removals`, every count up to it is kept and printed
+const MAX_REMOVALS: u64 = 1000;
+
+const POLICY_FLAGS: [&str; 7] = [
+    "--threads",
+    "--histogram",
+    "--explain",
+    "--min-step",
+    "--max-step",
+    "--monotonicity",
+    "--max-removals",
+];
+
+enum Mode {
+    Counts(Option<NonZeroUsize>),
+    Histogram,
+    Explain(Format),
+}
+
+/// Options of the modes that use a custom safety policy
+struct PolicyOptions {
+    mode: Mode,
+    policy: SafetyPolicy,
+    input: PathBuf,
+}
+
+/// Returns `None` if the arguments don't customize the safety policy
+fn parse_policy_args(args: Vec<String>) -> Result<Option<PolicyOptions>, String> {
+    if !args.iter().any(|arg| POLICY_FLAGS.contains(&arg.as_str())) {
+        return Ok(None);
+    }
+
+    let mut policy = SafetyPolicy::default();
+    let (mut min_step, mut max_step) = policy.steps.clone().into_inner();
+    let mut histogram = false;
+    let mut explain = false;
+    let mut format = None;
+    let mut threads = None;
+    let mut input = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut number = |flag: &str| {
+            args.next()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| format!("{} requires a non-negative integer", flag))
+        };
+        match arg.as_str() {
+            "--threads" => {
+                threads = Some(
+                    args.next()
+                        .and_then(|value| value.parse().ok())
+                        .ok_or("--threads requires a positive number of threads")?,
+                )
+            }
+            "--histogram" => histogram = true,
+            "--explain" => explain = true,
+            "--format" => format = Some(args.next().ok_or("--format requires 'text' or 'json'")?.parse()?),
+            "--min-step" => min_step = number(&arg)?,
+            "--max-step" => max_step = number(&arg)?,
+            "--max-removals" => {
+                let max_removals = number(&arg)?;
+                if max_removals > MAX_REMOVALS {
+                    return Err(format!("--max-removals can't be larger than {}", MAX_REMOVALS));
+                }
+                policy.max_removals = max_removals as usize;
+            }
+            "--monotonicity" => {
+                policy.monotonicity = args.next().ok_or("--monotonicity requires a kind")?.parse()?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            path => {
+                if input.replace(PathBuf::from(path)).is_some() {
+                    return Err("only a single input file can be provided".to_string());
+                }
+            }
+        }
+    }
+
+    if min_step > max_step {
+        return Err("--min-step can't be larger than --max-step".to_string());
+    }
+    policy.steps = min_step..=max_step;
+    if threads.is_some() && (histogram || explain) {
+        return Err("--threads can't be used together with --histogram or --explain".to_string());
+    }
+    let mode = match (histogram, explain, format) {
+        (false, false, None) => Mode::Counts(threads),
+        (true, false, None) => Mode::Histogram,
+        (false, true, format) => Mode::Explain(format.unwrap_or(Format::Text)),
+        (true, true, _) => return Err("--histogram and --explain can't be used together".to_string()),
+        (_, false, Some(_)) => return Err("--format is only supported by --explain".to_string()),
+    };
+    let input = input.unwrap_or_else(|| input_path(&default_inputs_dir(), 2));
+    Ok(Some(PolicyOptions { mode, policy, input }))
+}
+
+fn run(options: &PolicyOptions) -> Result<(), String> {
+    let name = input_name(&options.input);
+    let input =
+        read_input(&options.input).map_err(|e| format!("error: failed to read the input file '{}': {}", name, e))?;
+
+    match options.mode {
+        Mode::Counts(threads) => {
+            let counts = match threads {
+                Some(threads) => count_safe_reports_parallel(input.as_bytes(), &options.policy, threads),
+                None => count_safe_reports(input.as_bytes(), &options.policy),
+            }
+            .map_err(|e| e.render(&name, &input))?;
+            for (removals, count) in counts.iter().enumerate() {
+                println!("Safe reports with at most {} removed levels: {}", removals, count);
+            }
+        }
+        Mode::Histogram => {
+            let histogram =
+                removals_histogram(input.as_bytes(), &options.policy).map_err(|e| e.render(&name, &input))?;
+            for (removals, count) in histogram.iter().enumerate() {
+                println!("Reports that need {} removed levels: {}", removals, count);
+            }
+        }
+        Mode::Explain(format) => {
+            let diagnostics =
+                explain_reports(input.as_bytes(), &options.policy).map_err(|e| e.render(&name, &input))?;
+            match format {
+                Format::Text => diagnostics.iter().for_each(|diagnostic| println!("{}", diagnostic)),
+                Format::Json => {
+                    let diagnostics = diagnostics.iter().map(ToJson::to_json).collect();
+                    println!("{}", Json::Array(diagnostics).pretty());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_policy_args(env::args().skip(1).collect()) {
+        Ok(Some(options)) => options,
+        Ok(None) => return common::run::<Day2>(TEMPLATES),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
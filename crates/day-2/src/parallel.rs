@@ -0,0 +1,204 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Mutex;
+use std::thread;
+
+use common::{InputError, ParseError};
+
+use crate::{parse_report, tally, SafetyPolicy};
+
+/// Number of lines that are sent to a worker at once
+const BATCH_LINES: usize = 4096;
+
+/// Consecutive lines of the input
+struct Batch {
+    first_line_idx: usize,
+    lines: Vec<String>,
+}
+
+/// Sends the lines of the input to the workers in batches, stops early once a worker has found an invalid report
+fn send_batches<R: BufRead>(
+    reader: R,
+    batch_lines: usize,
+    sender: SyncSender<Batch>,
+    failed: &AtomicBool,
+) -> io::Result<()> {
+    let mut lines = reader.lines();
+    let mut first_line_idx = 0;
+    while !failed.load(Ordering::Relaxed) {
+        let batch = lines.by_ref().take(batch_lines).collect::<io::Result<Vec<_>>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        let len = batch.len();
+        if sender
+            .send(Batch {
+                first_line_idx,
+                lines: batch,
+            })
+            .is_err()
+        {
+            break;
+        }
+        first_line_idx += len;
+    }
+    Ok(())
+}
+
+/// Counts the safe reports of the received batches until the first invalid report.
+///
+/// Batches received after an invalid report are drained without being evaluated, so that the sender never blocks.
+fn count_batches(
+    batches: &Mutex<Receiver<Batch>>,
+    policy: &SafetyPolicy,
+    failed: &AtomicBool,
+) -> Result<Vec<usize>, ParseError> {
+    let mut counts = vec![0; policy.max_removals + 1];
+    let mut error = None;
+    // The lock is only held while waiting for the next batch
+    while let Ok(batch) = batches
+        .lock()
+        .expect("no worker should panic while holding the lock")
+        .recv()
+    {
+        if error.is_some() {
+            continue;
+        }
+        for (offset, line) in batch.lines.iter().enumerate() {
+            match parse_report(batch.first_line_idx + offset, line) {
+                Ok(levels) => tally(&mut counts, policy.removals_needed(&levels)),
+                Err(e) => {
+                    failed.store(true, Ordering::Relaxed);
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+    error.map_or(Ok(counts), Err)
+}
+
+fn count_in_batches<R: Read>(
+    src: R,
+    policy: &SafetyPolicy,
+    threads: NonZeroUsize,
+    batch_lines: usize,
+) -> Result<Vec<usize>, InputError> {
+    let (sender, receiver) = mpsc::sync_channel(threads.get());
+    let receiver = Mutex::new(receiver);
+    let failed = AtomicBool::new(false);
+    let (read_result, results) = thread::scope(|scope| {
+        let workers = (0..threads.get())
+            .map(|_| scope.spawn(|| count_batches(&receiver, policy, &failed)))
+            .collect::<Vec<_>>();
+        let read_result = send_batches(BufReader::new(src), batch_lines, sender, &failed);
+        let results = workers
+            .into_iter()
+            .map(|worker| worker.join().expect("worker thread shouldn't panic"))
+            .collect::<Vec<_>>();
+        (read_result, results)
+    });
+
+    let mut counts = vec![0; policy.max_removals + 1];
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(worker_counts) => counts
+                .iter_mut()
+                .zip(worker_counts)
+                .for_each(|(count, added)| *count += added),
+            Err(e) => errors.push(e),
+        }
+    }
+    // Every batch before an invalid report is evaluated, so the earliest error of the workers is the first of the input
+    if let Some(error) = errors.into_iter().min_by_key(|error| error.line) {
+        return Err(error.into());
+    }
+    read_result?;
+
+    Ok(counts)
+}
+
+/// Counts the safe reports like [`count_safe_reports`](crate::count_safe_reports), evaluating batches of lines on
+/// separate threads while the input is being read
+pub fn count_safe_reports_parallel<R: Read>(
+    src: R,
+    policy: &SafetyPolicy,
+    threads: NonZeroUsize,
+) -> Result<Vec<usize>, InputError> {
+    count_in_batches(src, policy, threads, BATCH_LINES)
+}
+
+/// Counts the safe reports according to the rules of both parts of the puzzle on `threads` threads
+pub fn find_safe_reports_parallel<R: Read>(src: R, threads: NonZeroUsize) -> Result<(usize, usize), InputError> {
+    let counts = count_safe_reports_parallel(src, &SafetyPolicy::default(), threads)?;
+    Ok((counts[0], counts[1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use common::{InputError, Rng};
+
+    use super::{count_in_batches, BATCH_LINES};
+    use crate::{count_safe_reports, count_safe_reports_parallel, find_safe_reports_parallel, SafetyPolicy};
+
+    #[test]
+    fn matches_sequential_counts() {
+        let mut rng = Rng::new(20);
+        for _ in 0..20 {
+            let mut input = String::new();
+            for _ in 0..rng.index(200) {
+                let levels = (0..rng.range(1..9))
+                    .map(|_| rng.range(0..15).to_string())
+                    .collect::<Vec<_>>();
+                input.push_str(&levels.join(" "));
+                input.push_str(if rng.chance(0.1) { "\r\n" } else { "\n" });
+            }
+            let policy = SafetyPolicy {
+                max_removals: rng.index(4),
+                ..SafetyPolicy::default()
+            };
+
+            let expected = count_safe_reports(input.as_bytes(), &policy).unwrap();
+            for threads in [1, 2, 3, 8, 300] {
+                let threads = NonZeroUsize::new(threads).unwrap();
+                assert_eq!(
+                    count_safe_reports_parallel(input.as_bytes(), &policy, threads).unwrap(),
+                    expected
+                );
+                for batch_lines in [1, 7, BATCH_LINES] {
+                    let counts = count_in_batches(input.as_bytes(), &policy, threads, batch_lines).unwrap();
+                    assert_eq!(counts, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reports_first_invalid_line() {
+        let input = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 x 4 1\n1 3 6 7 9\n1 y";
+        let Err(InputError::Parse(expected)) = count_safe_reports(input.as_bytes(), &SafetyPolicy::default()) else {
+            panic!("input should be rejected");
+        };
+
+        for threads in 1..=8 {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            let Err(InputError::Parse(error)) = find_safe_reports_parallel(input.as_bytes(), threads) else {
+                panic!("input should be rejected");
+            };
+            assert_eq!(error, expected);
+            for batch_lines in 1..=3 {
+                let policy = SafetyPolicy::default();
+                let Err(InputError::Parse(error)) = count_in_batches(input.as_bytes(), &policy, threads, batch_lines)
+                else {
+                    panic!("input should be rejected");
+                };
+                assert_eq!(error, expected);
+            }
+        }
+    }
+}
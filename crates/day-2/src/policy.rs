@@ -0,0 +1,360 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Directions in which the levels of a safe report are allowed to change
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Monotonicity {
+    /// Levels are either all increasing or all decreasing
+    #[default]
+    Strict,
+    /// Levels are either all non-decreasing or all non-increasing.
+    ///
+    /// Equal levels still have to satisfy the step range, so such policies usually start the range at 0.
+    NonStrict,
+    /// Every step can go in either direction
+    Either,
+}
+
+impl FromStr for Monotonicity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Monotonicity::Strict),
+            "non-strict" => Ok(Monotonicity::NonStrict),
+            "either" => Ok(Monotonicity::Either),
+            _ => Err(format!(
+                "unknown monotonicity '{}', expected 'strict', 'non-strict' or 'either'",
+                s
+            )),
+        }
+    }
+}
+
+/// Reason why a step between two adjacent levels breaks the rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// Levels started going in the opposite direction
+    DirectionChange,
+    /// Two adjacent levels are equal
+    ZeroStep,
+    /// Levels changed by more than the largest allowed step
+    StepTooLarge,
+    /// Levels changed by less than the smallest allowed step
+    StepTooSmall,
+}
+
+impl Violation {
+    /// Name of the violation in the machine-readable output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Violation::DirectionChange => "direction-change",
+            Violation::ZeroStep => "zero-step",
+            Violation::StepTooLarge => "step-too-large",
+            Violation::StepTooSmall => "step-too-small",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Violation::DirectionChange => "levels changed direction",
+            Violation::ZeroStep => "levels didn't change",
+            Violation::StepTooLarge => "levels changed too much",
+            Violation::StepTooSmall => "levels changed too little",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Rules that decide whether a report is safe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// Allowed absolute differences between adjacent levels
+    pub steps: RangeInclusive<u64>,
+    pub monotonicity: Monotonicity,
+    /// Maximum number of levels that can be removed to make a report safe
+    pub max_removals: usize,
+}
+
+impl Default for SafetyPolicy {
+    /// The rules of the puzzle: strictly monotonic levels with steps of 1 to 3 and a single removable level
+    fn default() -> Self {
+        SafetyPolicy {
+            steps: 1..=3,
+            monotonicity: Monotonicity::Strict,
+            max_removals: 1,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Returns the index of the first level that breaks the rules and the reason, or `None` if the report is safe as is
+    pub fn find_violation(&self, levels: &[i64]) -> Option<(usize, Violation)> {
+        let mut direction = Ordering::Equal;
+        for (idx, pair) in levels.windows(2).enumerate() {
+            let difference = pair[0].abs_diff(pair[1]);
+            let step = pair[1].cmp(&pair[0]);
+            let violation = if step.is_eq() && (self.monotonicity == Monotonicity::Strict || !self.steps.contains(&0)) {
+                Some(Violation::ZeroStep)
+            } else if difference > *self.steps.end() {
+                Some(Violation::StepTooLarge)
+            } else if difference < *self.steps.start() {
+                Some(Violation::StepTooSmall)
+            } else if self.monotonicity == Monotonicity::Either || step.is_eq() {
+                None
+            } else if direction.is_eq() {
+                direction = step;
+                None
+            } else {
+                (direction != step).then_some(Violation::DirectionChange)
+            };
+
+            if let Some(violation) = violation {
+                return Some((idx + 1, violation));
+            }
+        }
+        None
+    }
+
+    pub fn is_safe(&self, levels: &[i64]) -> bool {
+        self.find_violation(levels).is_none()
+    }
+
+    /// Checks a step between two adjacent levels of a report that goes in `direction`
+    fn is_step_allowed(&self, from: i64, to: i64, direction: Ordering) -> bool {
+        let step = to.cmp(&from);
+        let direction_allowed = match self.monotonicity {
+            Monotonicity::Strict => step == direction,
+            Monotonicity::NonStrict => step == direction || step.is_eq(),
+            Monotonicity::Either => true,
+        };
+        direction_allowed && self.steps.contains(&from.abs_diff(to))
+    }
+
+    /// Directions a safe report can go in, `Equal` stands for any direction
+    fn directions(&self) -> &'static [Ordering] {
+        match self.monotonicity {
+            Monotonicity::Strict | Monotonicity::NonStrict => &[Ordering::Less, Ordering::Greater],
+            Monotonicity::Either => &[Ordering::Equal],
+        }
+    }
+
+    /// Returns the index of the first level whose removal makes the report safe in O(n)
+    pub fn find_single_removal(&self, levels: &[i64]) -> Option<usize> {
+        let len = levels.len();
+        self.directions()
+            .iter()
+            .filter_map(|&direction| {
+                let allowed = |from: usize, to: usize| self.is_step_allowed(levels[from], levels[to], direction);
+
+                // `valid_prefix[idx]` tells whether `levels[..idx]` are safe,
+                // `valid_suffix[idx]` does the same for `levels[idx..]`
+                let mut valid_prefix = vec![true; len + 1];
+                for idx in 2..=len {
+                    valid_prefix[idx] = valid_prefix[idx - 1] && allowed(idx - 2, idx - 1);
+                }
+                let mut valid_suffix = vec![true; len + 1];
+                for idx in (0..len.saturating_sub(1)).rev() {
+                    valid_suffix[idx] = valid_suffix[idx + 1] && allowed(idx, idx + 1);
+                }
+
+                (0..len).find(|&idx| {
+                    valid_prefix[idx]
+                        && valid_suffix[idx + 1]
+                        && (idx == 0 || idx + 1 == len || allowed(idx - 1, idx + 1))
+                })
+            })
+            .min()
+    }
+
+    /// Returns the indices of the fewest levels whose removal makes the report safe, or `None` if it takes more than
+    /// `max_removals`
+    pub fn levels_to_remove(&self, levels: &[i64]) -> Option<Vec<usize>> {
+        if self.is_safe(levels) {
+            return Some(Vec::new());
+        }
+        if self.max_removals == 0 {
+            return None;
+        }
+        if let Some(idx) = self.find_single_removal(levels) {
+            return Some(vec![idx]);
+        }
+        // The quadratic search is only needed if more than one removal is allowed, which keeps the puzzle linear
+        if self.max_removals == 1 {
+            return None;
+        }
+        let kept = longest_safe_subsequence(levels, self);
+        Some((0..levels.len()).filter(|idx| !kept.contains(idx)).collect::<Vec<_>>())
+            .filter(|removed| removed.len() <= self.max_removals)
+    }
+
+    /// Returns the fewest removed levels that make the report safe, or `None` if it takes more than `max_removals`
+    pub fn removals_needed(&self, levels: &[i64]) -> Option<usize> {
+        self.levels_to_remove(levels).map(|removed| removed.len())
+    }
+}
+
+/// Returns the fewest levels that have to be removed to make the report safe, ignoring `max_removals`
+pub fn min_removals(report: &[i64], policy: &SafetyPolicy) -> usize {
+    report.len() - longest_safe_subsequence(report, policy).len()
+}
+
+/// Returns the indices of the levels that form the longest safe subsequence of the report in O(n^2)
+fn longest_safe_subsequence(report: &[i64], policy: &SafetyPolicy) -> Vec<usize> {
+    let mut best = Vec::new();
+    for &direction in policy.directions() {
+        // `longest[idx]` is the length of the longest safe subsequence that ends with `report[idx]`,
+        // `previous[idx]` is the level before `report[idx]` in that subsequence
+        let mut longest = vec![1; report.len()];
+        let mut previous = vec![None; report.len()];
+        for idx in 1..report.len() {
+            for prev in 0..idx {
+                if longest[prev] + 1 > longest[idx] && policy.is_step_allowed(report[prev], report[idx], direction) {
+                    longest[idx] = longest[prev] + 1;
+                    previous[idx] = Some(prev);
+                }
+            }
+        }
+
+        let Some(last) = (0..report.len()).max_by_key(|&idx| longest[idx]) else {
+            continue;
+        };
+        if longest[last] > best.len() {
+            best = std::iter::successors(Some(last), |&idx| previous[idx]).collect();
+            best.reverse();
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use crate::{min_removals, Monotonicity, SafetyPolicy, Violation};
+
+    #[test]
+    fn applies_monotonicity() {
+        let policy = |monotonicity| SafetyPolicy {
+            steps: 0..=3,
+            monotonicity,
+            max_removals: 0,
+        };
+        let strict = policy(Monotonicity::Strict);
+        let non_strict = policy(Monotonicity::NonStrict);
+        let either = policy(Monotonicity::Either);
+
+        assert_eq!(strict.find_violation(&[1, 2, 2, 3]), Some((2, Violation::ZeroStep)));
+        assert_eq!(non_strict.find_violation(&[1, 2, 2, 3]), None);
+        assert_eq!(
+            non_strict.find_violation(&[3, 3, 4, 2]),
+            Some((3, Violation::DirectionChange))
+        );
+        assert_eq!(either.find_violation(&[3, 3, 4, 2]), None);
+        assert_eq!(either.find_violation(&[3, 3, 7, 4]), Some((2, Violation::StepTooLarge)));
+    }
+
+    #[test]
+    fn applies_step_range() {
+        let policy = SafetyPolicy {
+            steps: 2..=3,
+            ..SafetyPolicy::default()
+        };
+
+        assert_eq!(policy.find_violation(&[1, 3, 3]), Some((2, Violation::ZeroStep)));
+        assert_eq!(policy.find_violation(&[1, 3, 4]), Some((2, Violation::StepTooSmall)));
+        assert_eq!(policy.find_violation(&[9, 7, 4]), None);
+    }
+
+    #[test]
+    fn finds_fewest_removals() {
+        let policy = SafetyPolicy {
+            max_removals: 3,
+            ..SafetyPolicy::default()
+        };
+
+        assert_eq!(policy.removals_needed(&[1, 3, 6, 7, 9]), Some(0));
+        assert_eq!(policy.removals_needed(&[1, 3, 2, 4, 5]), Some(1));
+        assert_eq!(policy.removals_needed(&[5, 1, 2, 9, 3, 4]), Some(2));
+        assert_eq!(policy.removals_needed(&[9, 1, 2, 3, 9, 0, 4]), Some(3));
+        assert_eq!(policy.removals_needed(&[1, 9, 2, 9, 3, 9, 4, 9]), None);
+    }
+
+    #[test]
+    fn checks_single_removal_like_min_removals() {
+        let mut rng = Rng::new(19);
+        let monotonicities = [Monotonicity::Strict, Monotonicity::NonStrict, Monotonicity::Either];
+        for _ in 0..2000 {
+            let min_step = rng.range(0..3) as u64;
+            let policy = SafetyPolicy {
+                steps: min_step..=min_step + rng.range(0..4) as u64,
+                monotonicity: *rng.choose(&monotonicities),
+                max_removals: 1,
+            };
+            let levels = (0..rng.index(10)).map(|_| rng.range(0..12)).collect::<Vec<_>>();
+
+            // The linear check has to agree with the full search whenever at most one level can be removed
+            let expected = Some(min_removals(&levels, &policy)).filter(|&removals| removals <= 1);
+            assert_eq!(policy.removals_needed(&levels), expected, "{:?} {:?}", policy, levels);
+        }
+    }
+
+    #[test]
+    fn finds_single_removal_like_brute_force() {
+        let mut rng = Rng::new(17);
+        let monotonicities = [Monotonicity::Strict, Monotonicity::NonStrict, Monotonicity::Either];
+        for _ in 0..2000 {
+            let min_step = rng.range(0..3) as u64;
+            let policy = SafetyPolicy {
+                steps: min_step..=min_step + rng.range(0..4) as u64,
+                monotonicity: *rng.choose(&monotonicities),
+                max_removals: 1,
+            };
+            let levels = (0..rng.index(10)).map(|_| rng.range(0..12)).collect::<Vec<_>>();
+
+            let expected = (0..levels.len()).find(|&idx| {
+                let mut rest = levels.clone();
+                rest.remove(idx);
+                policy.is_safe(&rest)
+            });
+            assert_eq!(
+                policy.find_single_removal(&levels),
+                expected,
+                "{:?} {:?}",
+                policy,
+                levels
+            );
+        }
+    }
+
+    #[test]
+    fn finds_min_removals_like_brute_force() {
+        let mut rng = Rng::new(18);
+        let monotonicities = [Monotonicity::Strict, Monotonicity::NonStrict, Monotonicity::Either];
+        for _ in 0..500 {
+            let min_step = rng.range(0..3) as u64;
+            let policy = SafetyPolicy {
+                steps: min_step..=min_step + rng.range(0..4) as u64,
+                monotonicity: *rng.choose(&monotonicities),
+                max_removals: 0,
+            };
+            let levels = (0..rng.index(10)).map(|_| rng.range(0..12)).collect::<Vec<_>>();
+
+            let expected = (0..1u32 << levels.len())
+                .filter_map(|kept| {
+                    let rest = (0..levels.len())
+                        .filter(|idx| kept & (1 << idx) != 0)
+                        .map(|idx| levels[idx])
+                        .collect::<Vec<_>>();
+                    policy.is_safe(&rest).then_some(levels.len() - rest.len())
+                })
+                .min()
+                .unwrap();
+            assert_eq!(min_removals(&levels, &policy), expected, "{:?} {:?}", policy, levels);
+        }
+    }
+}
@@ -1,96 +1,45 @@
-pub fn process_instructions(data: &str, enable_additional_instructions: bool) -> i64 {
-    let mut computation_result = 0;
-    let mut remaining = data;
-    let mut is_enabled = true;
-    loop {
-        let Some(start_pos) = remaining.find(['d', 'm']) else {
-            // No more muls/dos :(
-            break;
-        };
-        if remaining.as_bytes()[start_pos] == b'd' {
-            // Process the enable/disable instruction
-            if !&remaining
-                .as_bytes()
-                .get(start_pos..start_pos + 2)
-                .is_some_and(|instructon| instructon == b"do")
-            {
-                // Not a valid instruction
-                remaining = &remaining[start_pos + 1..];
-                continue;
-            }
-            if remaining
-                .as_bytes()
-                .get(start_pos..start_pos + 5)
-                .is_some_and(|instruction| instruction == b"don't")
-            {
-                // Disable all upcoming muls until enabled
-                is_enabled = false;
-                remaining = &remaining[start_pos + 5..];
-                continue;
-            } else {
-                // Enable all upcoming muls until disabled
-                is_enabled = true;
-                remaining = &remaining[start_pos + 2..];
-                continue;
-            }
-        } else {
-            // Process mul
-            if !remaining
-                .get(start_pos..start_pos + 3)
-                .is_some_and(|instruction| instruction == "mul")
-            {
-                // Not a mul :(
-                remaining = &remaining[start_pos + 1..];
-                continue;
-            }
-            remaining = &remaining[start_pos + 3..];
+mod near_miss;
+mod render;
+mod scanner;
+mod stream;
+mod vm;
+
+use common::{InputError, Solution};
+
+pub use near_miss::{find_near_misses, summarize_near_misses, NearMiss};
+pub use render::{render, RenderStyle};
+pub use scanner::{Instruction, InstructionKind, Operands, Reason, Scanner};
+pub use stream::{evaluate_stream, StreamScanner, DEFAULT_BUFFER_SIZE};
+pub use vm::{run, Definition, InstructionSet, Machine, Outcome, State, ADD, DO, DONT, MAX_ARITY, MUL, SKIP, SUB};
 
-            if !is_enabled && enable_additional_instructions {
-                // Instruction is disabled, continue to the next one
-                continue;
-            }
-            if remaining.as_bytes()[0] != b'(' {
-                // Malformed instruction, continue
-                continue;
-            }
-            remaining = &remaining[1..];
+/// Sums the results of the `mul` instructions, `enable_additional_instructions` enables `do()` and `don't()`
+pub fn process_instructions(data: &str, enable_additional_instructions: bool) -> i64 {
+    let set = InstructionSet::puzzle(enable_additional_instructions);
+    run(&set, Scanner::new(data.as_bytes(), &set))
+}
 
-            let Some(delim_pos) = remaining.find(',') else {
-                // No delim?
-                continue;
-            };
-            if delim_pos > 3 {
-                // Invalid instruction, continue to the next one
-                continue;
-            }
+pub struct Day3;
 
-            let Ok(first_num) = &remaining[..delim_pos].parse::<i64>() else {
-                // Bad number, continue to the next instruction
-                remaining = &remaining[delim_pos + 1..];
-                continue;
-            };
-            remaining = &remaining[delim_pos + 1..];
+impl Solution for Day3 {
+    const DAY: u8 = 3;
 
-            let Some(closing_bracket_pos) = remaining.find(')') else {
-                // No closing bracket?
-                continue;
-            };
-            if closing_bracket_pos > 3 {
-                // Invalid instruction, continue to the next one
-                continue;
-            }
+    type Input = Vec<Instruction>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-            let Ok(second_num) = &remaining[..closing_bracket_pos].parse::<i64>() else {
-                // Bad number, continue to the next instruction
-                remaining = &remaining[closing_bracket_pos + 1..];
-                continue;
-            };
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        // Corrupted memory is valid input by definition
+        // Both parts share the registered instructions and differ only in which of them are active
+        Ok(Scanner::new(input.as_bytes(), &InstructionSet::puzzle(true)).collect())
+    }
 
-            computation_result += first_num * second_num;
-        }
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        run(&InstructionSet::puzzle(false), input)
     }
 
-    computation_result
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        run(&InstructionSet::puzzle(true), input)
+    }
 }
 
 #[cfg(test)]
@@ -112,4 +61,10 @@ mod tests {
         let result = process_instructions(test_input, true);
         assert_eq!(result, 48);
     }
+
+    #[test]
+    fn handles_input_ending_with_an_instruction_name() {
+        assert_eq!(process_instructions("mul(2,3)mul", false), 6);
+        assert_eq!(process_instructions("mul(2,3)don", true), 6);
+    }
 }
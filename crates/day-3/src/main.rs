@@ -1,17 +1,201 @@
 use std::env;
+use std::fs::File;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use day_3::process_instructions;
+use common::{default_inputs_dir, input_name, input_path, STDIN};
+use day_3::{
+    evaluate_stream, find_near_misses, render, summarize_near_misses, Day3, InstructionSet, RenderStyle,
+    DEFAULT_BUFFER_SIZE,
+};
 
-fn main() {
-    let input_path = env::args().nth(1).expect("input file path is missing");
-    let data = std::fs::read_to_string(input_path).expect("error while reading the file");
+const USAGE: &str = "Usage:
+    day-3 [--format <text|json>] [INPUT]...
+    day-3 --stream [--buffer-size <BYTES>] [INPUT]
+    day-3 --near-misses [INPUT]
+    day-3 --render [--color <auto|always|never>] [INPUT]
 
-    let part_1 = process_instructions(&data, false);
-    let part_2 = process_instructions(&data, true);
+Options:
+    --stream                  Read the memory in fixed-size buffers, tolerating bytes that aren't valid UTF-8
+    --buffer-size <BYTES>     Size of the buffers the memory is read in [default: 65536]
+    --near-misses             List the instructions that weren't counted and why, along with a summary by reason
+    --render                  Print the memory with the counted instructions highlighted and the disabled parts dimmed
+    --color <WHEN>            Whether to use ANSI colors or brackets when rendering [default: auto]";
 
-    println!("Sum of results of all mul instructions: {}", part_1);
-    println!(
-        "Sum of results of all mul instructions with enable/disable instructions: {}",
-        part_2
-    );
+/// Templates of the answers in the text format
+const TEMPLATES: [&str; 2] = [
+    "Sum of results of all mul instructions: {}",
+    "Sum of results of all mul instructions with enable/disable instructions: {}",
+];
+
+/// Number of bytes of the memory that are shown for every near miss
+const EXCERPT_LEN: usize = 16;
+
+const MEMORY_FLAGS: [&str; 5] = ["--stream", "--buffer-size", "--near-misses", "--render", "--color"];
+
+enum Mode {
+    Stream {
+        buffer_size: usize,
+    },
+    NearMisses,
+    /// `None` picks the style depending on whether the output is a terminal
+    Render {
+        style: Option<RenderStyle>,
+    },
+}
+
+/// Options of the modes that inspect the memory byte by byte rather than as text
+struct MemoryOptions {
+    mode: Mode,
+    input: PathBuf,
+}
+
+/// Returns `None` if the arguments don't select any of the memory modes
+fn parse_memory_args(args: Vec<String>) -> Result<Option<MemoryOptions>, String> {
+    if !args.iter().any(|arg| MEMORY_FLAGS.contains(&arg.as_str())) {
+        return Ok(None);
+    }
+
+    let mut stream = None;
+    let mut near_misses = false;
+    let mut render = None;
+    let mut input = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stream" => {
+                stream.get_or_insert(DEFAULT_BUFFER_SIZE);
+            }
+            "--near-misses" => near_misses = true,
+            "--render" => {
+                render.get_or_insert(None);
+            }
+            "--color" => {
+                let style = match args.next().as_deref() {
+                    Some("auto") => None,
+                    Some("always") => Some(RenderStyle::Ansi),
+                    Some("never") => Some(RenderStyle::Plain),
+                    _ => return Err("--color requires one of 'auto', 'always' or 'never'".to_string()),
+                };
+                render = Some(style);
+            }
+            "--buffer-size" => {
+                stream = args
+                    .next()
+                    .and_then(|size| size.parse().ok())
+                    .filter(|size| *size > 0)
+                    .map(Some)
+                    .ok_or("--buffer-size requires a positive number of bytes")?
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            path => {
+                if input.replace(PathBuf::from(path)).is_some() {
+                    return Err("only a single input file can be provided".to_string());
+                }
+            }
+        }
+    }
+
+    let mode = match (stream, near_misses, render) {
+        (Some(buffer_size), false, None) => Mode::Stream { buffer_size },
+        (None, true, None) => Mode::NearMisses,
+        (None, false, Some(style)) => Mode::Render { style },
+        _ => return Err("only one of --stream, --near-misses and --render can be used".to_string()),
+    };
+    let input = input.unwrap_or_else(|| input_path(&default_inputs_dir(), 3));
+    Ok(Some(MemoryOptions { mode, input }))
+}
+
+fn open_input(path: &Path) -> Result<Box<dyn Read>, String> {
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file =
+        File::open(path).map_err(|e| format!("error: failed to read the input file '{}': {}", input_name(path), e))?;
+    Ok(Box::new(file))
+}
+
+fn run_stream(buffer_size: usize, path: &Path) -> Result<(), String> {
+    let (part_1, part_2) = evaluate_stream(open_input(path)?, buffer_size)
+        .map_err(|e| format!("error: failed to read '{}': {}", input_name(path), e))?;
+    println!("{}", TEMPLATES[0].replace("{}", &part_1.to_string()));
+    println!("{}", TEMPLATES[1].replace("{}", &part_2.to_string()));
+    Ok(())
+}
+
+fn read_memory(path: &Path) -> Result<Vec<u8>, String> {
+    let mut memory = Vec::new();
+    open_input(path)?
+        .read_to_end(&mut memory)
+        .map_err(|e| format!("error: failed to read '{}': {}", input_name(path), e))?;
+    Ok(memory)
+}
+
+fn run_near_misses(path: &Path) -> Result<(), String> {
+    let memory = read_memory(path)?;
+    let near_misses = find_near_misses(&memory, &InstructionSet::puzzle(true));
+    for near_miss in &near_misses {
+        let excerpt = &memory[near_miss.offset..memory.len().min(near_miss.offset + EXCERPT_LEN)];
+        // Line breaks and other control characters would break the output
+        let excerpt = String::from_utf8_lossy(excerpt)
+            .chars()
+            .map(|character| {
+                if character.is_control() {
+                    character.escape_debug().to_string()
+                } else {
+                    character.to_string()
+                }
+            })
+            .collect::<String>();
+        println!(
+            "{}: {}: {}: {}",
+            near_miss.offset, near_miss.name, near_miss.reason, excerpt
+        );
+    }
+
+    println!("Summary:");
+    for (reason, count) in summarize_near_misses(&near_misses) {
+        println!("    {}: {}", reason, count);
+    }
+    Ok(())
+}
+
+fn run_render(style: Option<RenderStyle>, path: &Path) -> Result<(), String> {
+    let memory = read_memory(path)?;
+    // Colors are only used by default when they can be shown, see https://no-color.org
+    let style = style.unwrap_or_else(|| {
+        if io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) {
+            RenderStyle::Ansi
+        } else {
+            RenderStyle::Plain
+        }
+    });
+    println!("{}", render(&memory, &InstructionSet::puzzle(true), style));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_memory_args(env::args().skip(1).collect()) {
+        Ok(Some(options)) => options,
+        Ok(None) => return common::run::<Day3>(TEMPLATES),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match options.mode {
+        Mode::Stream { buffer_size } => run_stream(buffer_size, &options.input),
+        Mode::NearMisses => run_near_misses(&options.input),
+        Mode::Render { style } => run_render(style, &options.input),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
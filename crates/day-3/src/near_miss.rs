@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use crate::scanner::{find_candidate, Candidate};
+use crate::{InstructionSet, Machine, Outcome, Reason};
+
+/// A candidate instruction that wasn't counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearMiss {
+    /// Byte offset of the candidate within the memory
+    pub offset: usize,
+    /// Name of the instruction the candidate starts with
+    pub name: &'static str,
+    pub reason: Reason,
+}
+
+/// Finds every candidate of an active instruction that was rejected or didn't take effect because it was disabled
+pub fn find_near_misses(memory: &[u8], set: &InstructionSet) -> Vec<NearMiss> {
+    let mut machine = Machine::new(set);
+    let mut near_misses = Vec::new();
+    let mut position = 0;
+    while let Some(candidate) = find_candidate(memory, position, memory.len(), set) {
+        match candidate {
+            Candidate::Instruction(instruction) => {
+                position = instruction.span.end;
+                if machine.execute(&instruction) == Outcome::Disabled {
+                    near_misses.push(NearMiss {
+                        offset: instruction.span.start,
+                        name: set.definition(instruction.opcode).name,
+                        reason: Reason::Disabled,
+                    });
+                }
+            }
+            Candidate::Rejected(rejection) => {
+                position = rejection.offset + 1;
+                near_misses.push(NearMiss {
+                    offset: rejection.offset,
+                    name: set.definition(rejection.opcode).name,
+                    reason: rejection.reason,
+                });
+            }
+        }
+    }
+    near_misses
+}
+
+/// Counts the near misses by reason
+pub fn summarize_near_misses(near_misses: &[NearMiss]) -> BTreeMap<Reason, usize> {
+    let mut summary = BTreeMap::new();
+    for near_miss in near_misses {
+        *summary.entry(near_miss.reason).or_insert(0) += 1;
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{find_near_misses, summarize_near_misses, InstructionSet, NearMiss, Reason};
+
+    #[test]
+    fn finds_rejected_candidates() {
+        let memory = b"mul(32,64]mul ( 2 , 4 )mul(1234,5)mul(a,1)mul(1 2)don't()mul(2,2)do()mul(3,3)don't(";
+
+        let near_misses = find_near_misses(memory, &InstructionSet::puzzle(true));
+        let expected = [
+            (0, "mul", Reason::MissingBracket),
+            (10, "mul", Reason::MissingBracket),
+            (23, "mul", Reason::OperandTooLong),
+            (34, "mul", Reason::NonNumericOperand),
+            (42, "mul", Reason::MissingSeparator),
+            (57, "mul", Reason::Disabled),
+            (77, "don't", Reason::MissingBracket),
+        ]
+        .map(|(offset, name, reason)| NearMiss { offset, name, reason });
+        assert_eq!(near_misses, expected);
+
+        let summary = summarize_near_misses(&near_misses);
+        assert_eq!(summary[&Reason::MissingBracket], 3);
+        assert_eq!(summary.get(&Reason::Disabled), Some(&1));
+    }
+
+    #[test]
+    fn ignores_inactive_instructions() {
+        let memory = b"xmul(2,4)&mul[3,7]!^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+
+        let near_misses = find_near_misses(memory, &InstructionSet::puzzle(false));
+        let offsets = near_misses.iter().map(|near_miss| near_miss.offset).collect::<Vec<_>>();
+        assert_eq!(offsets, [10, 36]);
+    }
+}
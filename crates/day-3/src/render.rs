@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use crate::{InstructionSet, Machine, Outcome, Scanner};
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// How the highlighted memory is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// ANSI colors for terminals
+    Ansi,
+    /// Counted instructions in `[]`, `do()` and `don't()` markers in `{}` and disabled stretches in `<>`
+    Plain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    /// Corrupted memory or an instruction that didn't take effect while the instructions are enabled
+    Enabled,
+    /// Corrupted memory or an instruction that didn't take effect while the instructions are disabled
+    Disabled,
+    /// A conditional instruction that was counted, e.g. `mul`
+    Counted,
+    /// An unconditional instruction that was applied, e.g. `do()` or `don't()`
+    Marker,
+}
+
+impl Highlight {
+    fn ansi_color(&self) -> Option<&'static str> {
+        match self {
+            Highlight::Enabled => None,
+            Highlight::Disabled => Some("\x1b[2m"),
+            Highlight::Counted => Some("\x1b[1;32m"),
+            Highlight::Marker => Some("\x1b[1;33m"),
+        }
+    }
+
+    fn brackets(&self) -> (&'static str, &'static str) {
+        match self {
+            Highlight::Enabled => ("", ""),
+            Highlight::Disabled => ("<", ">"),
+            Highlight::Counted => ("[", "]"),
+            Highlight::Marker => ("{", "}"),
+        }
+    }
+}
+
+/// Splits the memory into highlighted stretches, using the spans of the scanned instructions
+fn highlight(memory: &[u8], set: &InstructionSet) -> Vec<(Highlight, Range<usize>)> {
+    let mut stretches: Vec<(Highlight, Range<usize>)> = Vec::new();
+    let mut push = |highlight, span: Range<usize>| {
+        if span.is_empty() {
+            return;
+        }
+        match stretches.last_mut() {
+            // Adjacent memory with the same state is rendered as a single stretch
+            Some((last, last_span))
+                if *last == highlight && matches!(highlight, Highlight::Enabled | Highlight::Disabled) =>
+            {
+                last_span.end = span.end
+            }
+            _ => stretches.push((highlight, span)),
+        }
+    };
+
+    let mut machine = Machine::new(set);
+    let mut position = 0;
+    for instruction in Scanner::new(memory, set) {
+        let text = if machine.state.is_enabled {
+            Highlight::Enabled
+        } else {
+            Highlight::Disabled
+        };
+        push(text, position..instruction.span.start);
+
+        let highlight = match machine.execute(&instruction) {
+            Outcome::Applied if set.definition(instruction.opcode).conditional => Highlight::Counted,
+            Outcome::Applied => Highlight::Marker,
+            Outcome::Inactive | Outcome::Disabled | Outcome::Skipped => text,
+        };
+        position = instruction.span.end;
+        push(highlight, instruction.span);
+    }
+
+    let text = if machine.state.is_enabled {
+        Highlight::Enabled
+    } else {
+        Highlight::Disabled
+    };
+    push(text, position..memory.len());
+    stretches
+}
+
+/// Renders the memory with the counted instructions, the markers and the disabled stretches highlighted
+pub fn render(memory: &[u8], set: &InstructionSet, style: RenderStyle) -> String {
+    let mut rendered = String::with_capacity(memory.len());
+    for (highlight, span) in highlight(memory, set) {
+        // Spans start and end at ASCII bytes, so stretches never split a character
+        let text = String::from_utf8_lossy(&memory[span]);
+        match (style, highlight.ansi_color()) {
+            (RenderStyle::Ansi, Some(color)) => {
+                rendered.push_str(color);
+                rendered.push_str(&text);
+                rendered.push_str(ANSI_RESET);
+            }
+            (RenderStyle::Ansi, None) => rendered.push_str(&text),
+            (RenderStyle::Plain, _) => {
+                let (open, close) = highlight.brackets();
+                rendered.push_str(open);
+                rendered.push_str(&text);
+                rendered.push_str(close);
+            }
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{render, InstructionSet, RenderStyle};
+
+    const MEMORY: &[u8] = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn renders_plain_text() {
+        assert_eq!(
+            render(MEMORY, &InstructionSet::puzzle(true), RenderStyle::Plain),
+            "x[mul(2,4)]&mul[3,7]!^{don't()}<_mul(5,5)+mul(32,64](mul(11,8)un>{do()}?[mul(8,5)])"
+        );
+        assert_eq!(
+            render(MEMORY, &InstructionSet::puzzle(false), RenderStyle::Plain),
+            "x[mul(2,4)]&mul[3,7]!^don't()_[mul(5,5)]+mul(32,64]([mul(11,8)]undo()?[mul(8,5)])"
+        );
+    }
+
+    #[test]
+    fn renders_ansi_colors() {
+        let rendered = render(
+            b"mul(1,2)don't()x\xff",
+            &InstructionSet::puzzle(true),
+            RenderStyle::Ansi,
+        );
+
+        assert_eq!(
+            rendered,
+            "\x1b[1;32mmul(1,2)\x1b[0m\x1b[1;33mdon't()\x1b[0m\x1b[2mx\u{fffd}\x1b[0m"
+        );
+    }
+}
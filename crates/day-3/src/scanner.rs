@@ -0,0 +1,346 @@
+use std::fmt;
+use std::ops::{Deref, Range};
+
+use crate::{Definition, InstructionSet, MAX_ARITY};
+
+/// Operands of an instruction, which are stored inline as there are at most [`MAX_ARITY`] of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Operands {
+    values: [i64; MAX_ARITY],
+    len: usize,
+}
+
+impl Operands {
+    fn push(&mut self, value: i64) {
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+}
+
+impl Deref for Operands {
+    type Target = [i64];
+
+    fn deref(&self) -> &Self::Target {
+        &self.values[..self.len]
+    }
+}
+
+/// Typed view of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionKind {
+    Mul(i64, i64),
+    Do,
+    Dont,
+    /// Any other registered instruction
+    Custom {
+        opcode: usize,
+        operands: Operands,
+    },
+}
+
+/// A valid instruction found in the corrupted memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// Index of the instruction's definition within the instruction set it was found with
+    pub opcode: usize,
+    pub operands: Operands,
+    /// Byte range of the instruction within the memory
+    pub span: Range<usize>,
+}
+
+impl Instruction {
+    /// Returns the typed view of the instruction, the instructions of the puzzle are recognized by their names
+    pub fn kind(&self, set: &InstructionSet) -> InstructionKind {
+        match (set.definition(self.opcode).name, &*self.operands) {
+            ("mul", &[first, second]) => InstructionKind::Mul(first, second),
+            ("do", []) => InstructionKind::Do,
+            ("don't", []) => InstructionKind::Dont,
+            _ => InstructionKind::Custom {
+                opcode: self.opcode,
+                operands: self.operands,
+            },
+        }
+    }
+}
+
+/// Iterator over the valid instructions in corrupted memory, which can contain arbitrary bytes
+pub struct Scanner<'a> {
+    memory: &'a [u8],
+    set: &'a InstructionSet,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// Creates a scanner that looks for the active instructions of `set`
+    pub fn new(memory: &'a [u8], set: &'a InstructionSet) -> Self {
+        Scanner {
+            memory,
+            set,
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(instruction) = find_instruction(self.memory, self.position, self.memory.len(), self.set) else {
+            self.position = self.memory.len();
+            return None;
+        };
+        self.position = instruction.span.end;
+        Some(instruction)
+    }
+}
+
+/// Reason why a candidate instruction, which starts with a registered name, isn't valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Reason {
+    /// The name isn't followed by `(` or the operands aren't followed by `)`
+    MissingBracket,
+    /// Operands aren't separated by `,`
+    MissingSeparator,
+    /// An operand has more digits than allowed
+    OperandTooLong,
+    /// An operand is missing or isn't a number
+    NonNumericOperand,
+    /// The instruction is valid, but was disabled by `don't()`
+    Disabled,
+}
+
+impl Reason {
+    /// Name of the reason in the machine-readable output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reason::MissingBracket => "missing-bracket",
+            Reason::MissingSeparator => "missing-separator",
+            Reason::OperandTooLong => "operand-too-long",
+            Reason::NonNumericOperand => "non-numeric-operand",
+            Reason::Disabled => "disabled",
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Reason::MissingBracket => "missing bracket",
+            Reason::MissingSeparator => "missing separator between operands",
+            Reason::OperandTooLong => "operand is too long",
+            Reason::NonNumericOperand => "operand isn't a number",
+            Reason::Disabled => "disabled by don't()",
+        };
+        f.write_str(description)
+    }
+}
+
+/// A candidate instruction that turned out to be invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Rejection {
+    pub offset: usize,
+    pub opcode: usize,
+    pub reason: Reason,
+}
+
+pub(crate) enum Candidate {
+    Instruction(Instruction),
+    Rejected(Rejection),
+}
+
+/// Result of matching a single instruction definition
+enum Match {
+    Found(Operands, usize),
+    Rejected(Reason),
+    /// The name doesn't match, so it's not even a candidate
+    Mismatch,
+}
+
+/// Finds the first instruction that starts within `memory[from..until]`, its span is relative to `memory`
+pub(crate) fn find_instruction(memory: &[u8], from: usize, until: usize, set: &InstructionSet) -> Option<Instruction> {
+    let mut from = from;
+    loop {
+        match find_candidate(memory, from, until, set)? {
+            Candidate::Instruction(instruction) => return Some(instruction),
+            Candidate::Rejected(rejection) => from = rejection.offset + 1,
+        }
+    }
+}
+
+/// Finds the first active instruction or rejected candidate that starts within `memory[from..until]`.
+///
+/// Inactive instructions are skipped like any other corrupted memory. If several names match at the same position, an
+/// instruction wins over a rejection, and the rejection of the longest name wins over the other rejections.
+pub(crate) fn find_candidate(memory: &[u8], from: usize, until: usize, set: &InstructionSet) -> Option<Candidate> {
+    let mut start = from;
+    while start < until {
+        start += memory[start..until].iter().position(|byte| {
+            set.active_definitions()
+                .any(|(_, definition)| definition.name.as_bytes()[0] == *byte)
+        })?;
+
+        let mut rejection: Option<Rejection> = None;
+        for (opcode, definition) in set.active_definitions() {
+            match match_instruction(&memory[start..], definition) {
+                Match::Found(operands, len) => {
+                    return Some(Candidate::Instruction(Instruction {
+                        opcode,
+                        operands,
+                        span: start..start + len,
+                    }))
+                }
+                Match::Rejected(reason) => {
+                    if rejection
+                        .as_ref()
+                        .is_none_or(|other| definition.name.len() > set.definition(other.opcode).name.len())
+                    {
+                        rejection = Some(Rejection {
+                            offset: start,
+                            opcode,
+                            reason,
+                        });
+                    }
+                }
+                Match::Mismatch => {}
+            }
+        }
+        if let Some(rejection) = rejection {
+            return Some(Candidate::Rejected(rejection));
+        }
+        start += 1;
+    }
+    None
+}
+
+/// Matches the instruction at the start of `bytes`
+fn match_instruction(bytes: &[u8], definition: &Definition) -> Match {
+    if !bytes.starts_with(definition.name.as_bytes()) {
+        return Match::Mismatch;
+    }
+    let mut len = definition.name.len();
+
+    if bytes.get(len) != Some(&b'(') {
+        return Match::Rejected(Reason::MissingBracket);
+    }
+    len += 1;
+
+    let mut operands = Operands::default();
+    for idx in 0..definition.arity {
+        if idx > 0 {
+            if bytes.get(len) != Some(&b',') {
+                return Match::Rejected(Reason::MissingSeparator);
+            }
+            len += 1;
+        }
+        match match_operand(&bytes[len..], definition.max_operand_digits) {
+            Ok((operand, digits)) => {
+                operands.push(operand);
+                len += digits;
+            }
+            Err(reason) => return Match::Rejected(reason),
+        }
+    }
+    if bytes.get(len) != Some(&b')') {
+        return Match::Rejected(Reason::MissingBracket);
+    }
+
+    Match::Found(operands, len + 1)
+}
+
+/// Matches an operand of 1 to `max_digits` digits, returning it together with its length
+fn match_operand(bytes: &[u8], max_digits: usize) -> Result<(i64, usize), Reason> {
+    let len = bytes.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    if len == 0 {
+        return Err(Reason::NonNumericOperand);
+    }
+    if len > max_digits {
+        return Err(Reason::OperandTooLong);
+    }
+
+    let value = bytes[..len]
+        .iter()
+        .try_fold(0i64, |value, digit| {
+            value.checked_mul(10)?.checked_add(i64::from(digit - b'0'))
+        })
+        .ok_or(Reason::OperandTooLong)?;
+    Ok((value, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use crate::{Definition, InstructionKind, InstructionSet, Scanner};
+
+    #[test]
+    fn finds_instructions_with_spans() {
+        let set = InstructionSet::puzzle(true);
+        let memory = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+        let instructions = Scanner::new(memory, &set)
+            .map(|instruction| (instruction.kind(&set), instruction.span))
+            .collect::<Vec<_>>();
+        let expected = [
+            (InstructionKind::Mul(2, 4), 1..9),
+            (InstructionKind::Dont, 20..27),
+            (InstructionKind::Mul(5, 5), 28..36),
+            (InstructionKind::Mul(11, 8), 48..57),
+            (InstructionKind::Do, 59..63),
+            (InstructionKind::Mul(8, 5), 64..72),
+        ];
+        assert_eq!(instructions, expected);
+    }
+
+    #[test]
+    fn falls_back_to_custom_instructions() {
+        let mut set = InstructionSet::builtin();
+        set.register(Definition {
+            name: "mul",
+            arity: 3,
+            max_operand_digits: 3,
+            conditional: true,
+            effect: |state, operands| state.accumulator += operands.iter().product::<i64>(),
+        });
+
+        let instructions = Scanner::new(b"add(1,2)mul(2,3,4)", &set).collect::<Vec<_>>();
+        let InstructionKind::Custom { opcode, operands } = instructions[0].kind(&set) else {
+            panic!("add isn't an instruction of the puzzle");
+        };
+        assert_eq!((opcode, &*operands), (set.opcode("add").unwrap(), &[1, 2][..]));
+        // A replaced instruction with a different arity isn't the puzzle's `mul` anymore
+        assert!(matches!(instructions[1].kind(&set), InstructionKind::Custom { .. }));
+    }
+
+    #[test]
+    fn rejects_malformed_instructions() {
+        let set = InstructionSet::builtin();
+        let memory = b"mul(1234,5)mul(1,)mul(-1,2)mul (1,2)do(don't(add(1)skip(1,2)mul(4,5mul";
+
+        assert_eq!(Scanner::new(memory, &set).count(), 0);
+    }
+
+    #[test]
+    fn skips_inactive_instructions() {
+        let set = InstructionSet::puzzle(false);
+        let memory = b"add(1,2)don't()mul(2,3)skip(1)do()sub(4,5)";
+
+        let instructions = Scanner::new(memory, &set)
+            .map(|instruction| (instruction.kind(&set), instruction.span))
+            .collect::<Vec<_>>();
+        assert_eq!(instructions, [(InstructionKind::Mul(2, 3), 15..23)]);
+    }
+
+    #[test]
+    fn never_panics_on_arbitrary_bytes() {
+        let set = InstructionSet::builtin();
+        let mut rng = Rng::new(21);
+        let alphabet = b"mul(do,n't)123\xff\xc3";
+        for _ in 0..1000 {
+            let memory = (0..rng.index(40)).map(|_| *rng.choose(alphabet)).collect::<Vec<_>>();
+
+            for instruction in Scanner::new(&memory, &set) {
+                assert!(instruction.span.end <= memory.len());
+            }
+        }
+    }
+}
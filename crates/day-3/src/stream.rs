@@ -0,0 +1,149 @@
+use std::io::{self, ErrorKind, Read};
+
+use crate::scanner::find_instruction;
+use crate::{Instruction, InstructionSet, Machine};
+
+/// Default size of the buffers the memory is read in
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Iterator over the valid instructions of memory that is read in fixed-size buffers.
+///
+/// Instructions can span several buffers, their spans are byte offsets from the start of the stream.
+pub struct StreamScanner<'a, R> {
+    src: R,
+    set: &'a InstructionSet,
+    chunk: Vec<u8>,
+    /// Bytes that haven't been scanned yet, along with the ones that were already scanned
+    pending: Vec<u8>,
+    /// Offset of the start of `pending` within the stream
+    offset: usize,
+    /// Position within `pending` to continue scanning from
+    position: usize,
+    is_finished: bool,
+}
+
+impl<'a, R: Read> StreamScanner<'a, R> {
+    /// Creates a scanner that reads `buffer_size` bytes at a time, which must not be zero
+    pub fn new(src: R, set: &'a InstructionSet, buffer_size: usize) -> Self {
+        assert!(buffer_size > 0, "buffer size must not be zero");
+        StreamScanner {
+            src,
+            set,
+            chunk: vec![0; buffer_size],
+            pending: Vec::new(),
+            offset: 0,
+            position: 0,
+            is_finished: false,
+        }
+    }
+
+    /// Reads the next buffer, dropping the bytes that were already scanned
+    fn refill(&mut self) -> io::Result<()> {
+        self.pending.drain(..self.position);
+        self.offset += self.position;
+        self.position = 0;
+
+        loop {
+            match self.src.read(&mut self.chunk) {
+                Ok(0) => self.is_finished = true,
+                Ok(len) => self.pending.extend_from_slice(&self.chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+            return Ok(());
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamScanner<'_, R> {
+    type Item = io::Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Whether an instruction starts at some position is only known once enough bytes after it were read
+            let scannable = if self.is_finished {
+                self.pending.len()
+            } else {
+                self.pending.len().saturating_sub(self.set.lookahead() - 1)
+            };
+
+            if let Some(mut instruction) = find_instruction(&self.pending, self.position, scannable, self.set) {
+                self.position = instruction.span.end;
+                instruction.span = self.offset + instruction.span.start..self.offset + instruction.span.end;
+                return Some(Ok(instruction));
+            }
+            self.position = self.position.max(scannable);
+
+            if self.is_finished {
+                return None;
+            }
+            if let Err(e) = self.refill() {
+                self.is_finished = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Evaluates the memory read from `src` in buffers of `buffer_size` bytes, returning the results of both parts
+pub fn evaluate_stream<R: Read>(src: R, buffer_size: usize) -> io::Result<(i64, i64)> {
+    let sets = [InstructionSet::puzzle(false), InstructionSet::puzzle(true)];
+    let mut machines = sets.each_ref().map(Machine::new);
+    for instruction in StreamScanner::new(src, &sets[1], buffer_size) {
+        let instruction = instruction?;
+        for machine in &mut machines {
+            machine.execute(&instruction);
+        }
+    }
+    Ok((machines[0].accumulator(), machines[1].accumulator()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::{evaluate_stream, process_instructions, InstructionSet, Scanner, StreamScanner};
+
+    const MEMORY: &[u8] =
+        b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?\xffmul(8,5))mul(123,456)\xc3(mul(1234,5)";
+
+    fn evaluate_in_memory(memory: &[u8]) -> (i64, i64) {
+        // Invalid UTF-8 can't be a part of an instruction, so it's safe to replace it
+        let memory = String::from_utf8_lossy(memory);
+        (
+            process_instructions(&memory, false),
+            process_instructions(&memory, true),
+        )
+    }
+
+    #[test]
+    fn handles_every_buffer_size() {
+        let expected = evaluate_in_memory(MEMORY);
+        let set = InstructionSet::builtin();
+        let expected_instructions = Scanner::new(MEMORY, &set).collect::<Vec<_>>();
+
+        for buffer_size in 1..=MEMORY.len() + 1 {
+            assert_eq!(
+                evaluate_stream(MEMORY, buffer_size).unwrap(),
+                expected,
+                "{}",
+                buffer_size
+            );
+
+            let instructions = StreamScanner::new(MEMORY, &set, buffer_size)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(instructions, expected_instructions);
+        }
+    }
+
+    #[test]
+    fn handles_every_split_point() {
+        let expected = evaluate_in_memory(MEMORY);
+
+        for split in 0..=MEMORY.len() {
+            let (head, tail) = MEMORY.split_at(split);
+            assert_eq!(evaluate_stream(head.chain(tail), 4096).unwrap(), expected, "{}", split);
+        }
+    }
+}
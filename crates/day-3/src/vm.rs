@@ -0,0 +1,302 @@
+use std::borrow::Borrow;
+
+use crate::Instruction;
+
+/// State of the machine that the instructions act on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub accumulator: i64,
+    /// Whether the conditional instructions take effect
+    pub is_enabled: bool,
+    /// Whether the next active instruction that isn't disabled has to be skipped
+    pub skip_next: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            accumulator: 0,
+            is_enabled: true,
+            skip_next: false,
+        }
+    }
+}
+
+/// Largest number of operands an instruction can have
+pub const MAX_ARITY: usize = 4;
+
+/// Definition of an instruction that looks like `name(operand,...)`
+#[derive(Debug, Clone, Copy)]
+pub struct Definition {
+    pub name: &'static str,
+    /// Number of comma-separated operands, at most [`MAX_ARITY`]
+    pub arity: usize,
+    /// Largest number of digits in an operand
+    pub max_operand_digits: usize,
+    /// Whether the instruction is ignored while disabled by `don't()`
+    pub conditional: bool,
+    /// Applies the instruction to the state, the operands are guaranteed to match the arity
+    pub effect: fn(&mut State, &[i64]),
+}
+
+impl Definition {
+    /// Number of bytes that are enough to tell whether the instruction starts at some position
+    fn lookahead(&self) -> usize {
+        // Every operand is followed by a separator, one more digit is needed to reject a long operand
+        self.name.len() + "(".len() + (self.arity * (self.max_operand_digits + 1)).max(")".len())
+    }
+}
+
+/// Multiplies the operands and adds the product to the accumulator
+pub const MUL: Definition = Definition {
+    name: "mul",
+    arity: 2,
+    max_operand_digits: 3,
+    conditional: true,
+    effect: |state, operands| state.accumulator += operands[0] * operands[1],
+};
+
+/// Adds the sum of the operands to the accumulator
+pub const ADD: Definition = Definition {
+    name: "add",
+    arity: 2,
+    max_operand_digits: 3,
+    conditional: true,
+    effect: |state, operands| state.accumulator += operands[0] + operands[1],
+};
+
+/// Adds the difference of the operands to the accumulator
+pub const SUB: Definition = Definition {
+    name: "sub",
+    arity: 2,
+    max_operand_digits: 3,
+    conditional: true,
+    effect: |state, operands| state.accumulator += operands[0] - operands[1],
+};
+
+/// Enables the upcoming instructions
+pub const DO: Definition = Definition {
+    name: "do",
+    arity: 0,
+    max_operand_digits: 0,
+    conditional: false,
+    effect: |state, _| state.is_enabled = true,
+};
+
+/// Disables the upcoming instructions until the next `do()`
+pub const DONT: Definition = Definition {
+    name: "don't",
+    arity: 0,
+    max_operand_digits: 0,
+    conditional: false,
+    effect: |state, _| state.is_enabled = false,
+};
+
+/// Skips the next instruction if the accumulator is larger than the operand
+pub const SKIP: Definition = Definition {
+    name: "skip",
+    arity: 1,
+    max_operand_digits: 3,
+    conditional: true,
+    effect: |state, operands| state.skip_next = state.accumulator > operands[0],
+};
+
+/// Registry of the instructions that can be found in memory, along with the ones that take effect
+#[derive(Debug, Clone, Default)]
+pub struct InstructionSet {
+    definitions: Vec<Definition>,
+    active: Vec<bool>,
+}
+
+impl InstructionSet {
+    /// All the built-in instructions, every one of them is active
+    pub fn builtin() -> Self {
+        let mut set = InstructionSet::default();
+        for definition in [MUL, ADD, SUB, DO, DONT, SKIP] {
+            set.register(definition);
+        }
+        set
+    }
+
+    /// Built-in instructions with only the ones of the puzzle active, `do()` and `don't()` are active only if
+    /// `enable_additional_instructions` is set
+    pub fn puzzle(enable_additional_instructions: bool) -> Self {
+        let mut set = InstructionSet::builtin();
+        set.activate_only(&["mul"]);
+        if enable_additional_instructions {
+            set.set_active("do", true);
+            set.set_active("don't", true);
+        }
+        set
+    }
+
+    /// Adds an active instruction, replacing the one with the same name
+    pub fn register(&mut self, definition: Definition) {
+        assert!(!definition.name.is_empty(), "instruction name must not be empty");
+        assert!(
+            definition.arity <= MAX_ARITY,
+            "instruction can have at most {} operands",
+            MAX_ARITY
+        );
+        match self.opcode(definition.name) {
+            Some(opcode) => {
+                self.definitions[opcode] = definition;
+                self.active[opcode] = true;
+            }
+            None => {
+                self.definitions.push(definition);
+                self.active.push(true);
+            }
+        }
+    }
+
+    /// Returns `false` if there is no such instruction
+    pub fn set_active(&mut self, name: &str, active: bool) -> bool {
+        let Some(opcode) = self.opcode(name) else {
+            return false;
+        };
+        self.active[opcode] = active;
+        true
+    }
+
+    /// Makes only the given instructions active, returns the names that aren't registered
+    pub fn activate_only<'a>(&mut self, names: &[&'a str]) -> Vec<&'a str> {
+        self.active.fill(false);
+        names
+            .iter()
+            .copied()
+            .filter(|name| !self.set_active(name, true))
+            .collect()
+    }
+
+    pub fn opcode(&self, name: &str) -> Option<usize> {
+        self.definitions.iter().position(|definition| definition.name == name)
+    }
+
+    pub fn definition(&self, opcode: usize) -> &Definition {
+        &self.definitions[opcode]
+    }
+
+    pub fn definitions(&self) -> &[Definition] {
+        &self.definitions
+    }
+
+    pub fn is_active(&self, opcode: usize) -> bool {
+        self.active[opcode]
+    }
+
+    /// Active instructions along with their opcodes
+    pub(crate) fn active_definitions(&self) -> impl Iterator<Item = (usize, &Definition)> {
+        self.definitions
+            .iter()
+            .enumerate()
+            .filter(|(opcode, _)| self.active[*opcode])
+    }
+
+    /// Number of bytes that are enough to tell whether any active instruction starts at some position
+    pub(crate) fn lookahead(&self) -> usize {
+        self.active_definitions()
+            .map(|(_, definition)| definition.lookahead())
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+/// What happened to an executed instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Applied,
+    /// The instruction isn't active in the instruction set
+    Inactive,
+    /// The instruction is conditional and was disabled by `don't()`
+    Disabled,
+    /// The instruction was skipped by a preceding instruction
+    Skipped,
+}
+
+/// Executes instructions found with an instruction set that has the same registered instructions
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    set: &'a InstructionSet,
+    pub state: State,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(set: &'a InstructionSet) -> Self {
+        Machine {
+            set,
+            state: State::default(),
+        }
+    }
+
+    /// Applies the instruction if it's active, enabled and isn't skipped
+    pub fn execute(&mut self, instruction: &Instruction) -> Outcome {
+        let definition = self.set.definition(instruction.opcode);
+        if !self.set.is_active(instruction.opcode) {
+            return Outcome::Inactive;
+        }
+        if definition.conditional && !self.state.is_enabled {
+            return Outcome::Disabled;
+        }
+        if self.state.skip_next {
+            self.state.skip_next = false;
+            return Outcome::Skipped;
+        }
+        (definition.effect)(&mut self.state, &instruction.operands);
+        Outcome::Applied
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.state.accumulator
+    }
+}
+
+/// Executes the instructions on a new machine, returning the accumulator
+pub fn run<I: IntoIterator<Item: Borrow<Instruction>>>(set: &InstructionSet, instructions: I) -> i64 {
+    let mut machine = Machine::new(set);
+    for instruction in instructions {
+        machine.execute(instruction.borrow());
+    }
+    machine.accumulator()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{run, Definition, InstructionSet, Scanner};
+
+    #[test]
+    fn executes_builtin_instructions() {
+        let set = InstructionSet::builtin();
+        let memory = b"add(1,2)mul(3,4)?sub(1,5)don't()mul(9,9)do()skip(10)mul(2,2)skip(100)add(0,1)";
+
+        let instructions = Scanner::new(memory, &set).collect::<Vec<_>>();
+        assert_eq!(instructions.len(), 10);
+        assert_eq!(run(&set, &instructions), 3 + 12 - 4 + 1);
+    }
+
+    #[test]
+    fn ignores_inactive_instructions() {
+        let mut set = InstructionSet::builtin();
+        let memory = b"mul(2,3)add(1,1)don't()sub(9,1)";
+        let instructions = Scanner::new(memory, &set).collect::<Vec<_>>();
+
+        assert_eq!(set.activate_only(&["mul", "sub", "jmp"]), ["jmp"]);
+        assert_eq!(run(&set, &instructions), 6 + 8);
+        assert_eq!(run(&InstructionSet::puzzle(true), &instructions), 6);
+    }
+
+    #[test]
+    fn supports_registered_instructions() {
+        let mut set = InstructionSet::puzzle(true);
+        set.register(Definition {
+            name: "square",
+            arity: 1,
+            max_operand_digits: 4,
+            conditional: false,
+            effect: |state, operands| state.accumulator += operands[0] * operands[0],
+        });
+        let memory = b"square(1000)mul(1000,1)square(12345)mul(2,2)";
+
+        assert_eq!(run(&set, Scanner::new(memory, &set)), 1_000_000 + 4);
+    }
+}
@@ -1,141 +1,105 @@
+use common::{InputError, Solution};
+use grid::{Direction, Grid, Point};
+
 const XMAS: &[u8; 4] = b"XMAS";
 
-fn has_word_at_indices<const N: usize>(
-    data: &[Vec<u8>],
-    word: &[u8; N],
-    indices: [(i64, i64); N],
-    is_spelled_backwards: bool,
-) -> bool {
-    for (letter_idx, (i, j)) in indices.into_iter().enumerate() {
-        if j < 0 {
-            // Can happen when looking for the left diagonal item
-            return false;
-        }
+/// Checks whether the word is spelled starting at the given point and moving in the given direction
+fn has_word_at(data: &Grid<u8>, word: &[u8], start: Point, direction: Direction) -> bool {
+    word.iter()
+        .enumerate()
+        .all(|(letter_idx, letter)| data.get(start + direction.offset() * letter_idx as i64) == Some(letter))
+}
 
-        let to_be_letter_idx = if is_spelled_backwards {
-            word.len() - 1 - letter_idx
-        } else {
-            letter_idx
-        };
+/// Checks whether the diagonal passing through the given point has an "MAS" spelled in any direction
+fn has_mas_on_diagonal(data: &Grid<u8>, center: Point, direction: Direction) -> bool {
+    has_word_at(data, &XMAS[1..], center.step(direction.opposite()), direction)
+        || has_word_at(data, &XMAS[1..], center.step(direction), direction.opposite())
+}
 
-        let Some(letter) = data.get(i as usize).and_then(|row| row.get(j as usize)) else {
-            // One of the indices is not valid
-            return false;
-        };
+/// Counts the XMAS words that start at the given point, in all directions
+fn count_xmas_at(data: &Grid<u8>, point: Point) -> usize {
+    Direction::ALL
+        .into_iter()
+        .filter(|direction| has_word_at(data, XMAS, point, *direction))
+        .count()
+}
 
-        if *letter != word[to_be_letter_idx] {
-            // Wrong letter encountered
-            return false;
-        }
-    }
-    true
+/// Checks whether an X-shaped MAS is centered at the given point
+fn has_x_shaped_mas_at(data: &Grid<u8>, point: Point) -> bool {
+    has_mas_on_diagonal(data, point, Direction::DownRight) && has_mas_on_diagonal(data, point, Direction::DownLeft)
 }
 
-pub fn find_words(data: &[Vec<u8>]) -> (usize, usize) {
+pub fn count_xmas(data: &Grid<u8>) -> usize {
+    data.iter()
+        .filter(|(_, character)| **character == b'X')
+        .map(|(point, _)| count_xmas_at(data, point))
+        .sum()
+}
+
+pub fn count_x_shaped_mas(data: &Grid<u8>) -> usize {
+    data.iter()
+        .filter(|(point, character)| **character == b'A' && has_x_shaped_mas_at(data, *point))
+        .count()
+}
+
+pub fn find_words(data: &Grid<u8>) -> (usize, usize) {
     let mut xmas_occurnces = 0;
     let mut x_shaped_mas_occurences = 0;
-    for (i, row) in data.iter().enumerate() {
-        for (j, character) in row.iter().enumerate() {
-            if *character != b'X' && *character != b'S' && *character != b'A' {
-                continue;
-            }
-            if *character == b'A' {
-                // Look for an X-shaped MAS
-
-                let is_diagonal_left_spelled_backwards = {
-                    let Some(row_idx) = i.checked_sub(1) else {
-                        // No row above the current one
-                        continue;
-                    };
-                    let Some(character_idx) = j.checked_sub(1) else {
-                        // No character before the current one
-                        continue;
-                    };
-                    data.get(row_idx)
-                        .and_then(|row| row.get(character_idx))
-                        .is_some_and(|character: &u8| *character == b'S')
-                };
-
-                let is_diagonal_right_spelled_backwards = {
-                    let Some(row_idx) = i.checked_sub(1) else {
-                        // No row above the current one
-                        continue;
-                    };
-                    data.get(row_idx)
-                        .and_then(|row| row.get(j + 1))
-                        .is_some_and(|character: &u8| *character == b'S')
-                };
-
-                let i = i as i64;
-                let j = j as i64;
-                let diagonal_left_word_indices = [(i - 1, j - 1), (i, j), (i + 1, j + 1)];
-                let diagonal_right_word_indices = [(i - 1, j + 1), (i, j), (i + 1, j - 1)];
-
-                if has_word_at_indices(
-                    data,
-                    &XMAS[1..].try_into().unwrap(),
-                    diagonal_left_word_indices,
-                    is_diagonal_left_spelled_backwards,
-                ) && has_word_at_indices(
-                    data,
-                    &XMAS[1..].try_into().unwrap(),
-                    diagonal_right_word_indices,
-                    is_diagonal_right_spelled_backwards,
-                ) {
-                    x_shaped_mas_occurences += 1;
-                }
-            } else {
-                // Look for an XMAS
-                let is_spelled_backwards = *character == b'S';
-
-                // Convert character indices to integer to allow them becoming negative (when checking left diagonal items)
-                let i = i as i64;
-                let j = j as i64;
-
-                let horizontal_word_indices = [(i, j), (i, j + 1), (i, j + 2), (i, j + 3)];
-                let vertical_word_indices = [(i, j), (i + 1, j), (i + 2, j), (i + 3, j)];
-                let diagonal_left_word_indices = [(i, j), (i + 1, j - 1), (i + 2, j - 2), (i + 3, j - 3)];
-                let diagonal_right_word_indices = [(i, j), (i + 1, j + 1), (i + 2, j + 2), (i + 3, j + 3)];
-
-                for indices_set in [
-                    horizontal_word_indices,
-                    vertical_word_indices,
-                    diagonal_left_word_indices,
-                    diagonal_right_word_indices,
-                ] {
-                    if has_word_at_indices(data, XMAS, indices_set, is_spelled_backwards) {
-                        xmas_occurnces += 1;
-                    }
-                }
-            }
+    for (point, character) in data.iter() {
+        match character {
+            // Look for an XMAS in all directions
+            b'X' => xmas_occurnces += count_xmas_at(data, point),
+            // Look for an X-shaped MAS
+            b'A' if has_x_shaped_mas_at(data, point) => x_shaped_mas_occurences += 1,
+            _ => {}
         }
     }
 
     (xmas_occurnces, x_shaped_mas_occurences)
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(Grid::parse(input, |letter| letter)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_xmas(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        count_x_shaped_mas(input)
+    }
+
+    fn solve(input: &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        find_words(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::find_words;
+    use grid::Grid;
+
+    use crate::{count_x_shaped_mas, count_xmas, find_words};
 
     #[test]
     fn correctly_calculates_both_parts() {
-        let test_data: &[Vec<u8>] = &[
-            b"MMMSXXMASM".to_vec(),
-            b"MSAMXMSMSA".to_vec(),
-            b"AMXSXMAAMM".to_vec(),
-            b"MSAMASMSMX".to_vec(),
-            b"XMASAMXAMM".to_vec(),
-            b"XXAMMXXAMA".to_vec(),
-            b"SMSMSASXSS".to_vec(),
-            b"SAXAMASAAA".to_vec(),
-            b"MAMMMXMMMM".to_vec(),
-            b"MXMXAXMASX".to_vec(),
-        ];
-
-        let (part_1, part_2) = find_words(test_data);
+        let test_data = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\nXXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX";
+        let test_data = Grid::parse(test_data, |letter| letter).unwrap();
+
+        let (part_1, part_2) = find_words(&test_data);
 
         assert_eq!(part_1, 18);
-        assert_eq!(part_2, 9)
+        assert_eq!(part_2, 9);
+        assert_eq!(count_xmas(&test_data), part_1);
+        assert_eq!(count_x_shaped_mas(&test_data), part_2);
     }
 }
@@ -1,24 +1,35 @@
 use std::collections::HashMap;
 use std::io::{BufRead as _, BufReader, Read};
 
-fn process_rule(line: &str) -> (u64, u64) {
-    line.split_once('|')
-        .and_then(|(num_1, num_2)| {
-            num_1
-                .parse::<u64>()
-                .ok()
-                .and_then(|num_1| num_2.parse::<u64>().ok().map(|num_2| (num_1, num_2)))
-        })
-        .expect("failed to parse numbers from a line")
+use common::{parse_token, InputError, ParseError, Solution};
+
+fn process_rule(line_idx: usize, line: &str) -> Result<(u64, u64), ParseError> {
+    let Some((num_1, num_2)) = line.split_once('|') else {
+        return Err(ParseError::at_token(line_idx, line, line, "a rule in the 'X|Y' format"));
+    };
+
+    Ok((
+        parse_token(line_idx, line, num_1, "a page number")?,
+        parse_token(line_idx, line, num_2, "a page number")?,
+    ))
 }
 
-fn process_update(line: &str, page_numbers: &mut Vec<u64>, rules: &HashMap<u64, Vec<u64>>) -> bool {
+fn process_update(
+    line_idx: usize,
+    line: &str,
+    page_numbers: &mut Vec<u64>,
+    rules: &HashMap<u64, Vec<u64>>,
+) -> Result<bool, ParseError> {
     // Process the update
-    line.split(',').for_each(|page| {
-        let page = page.parse::<u64>().expect("failed to parse the page number");
-        page_numbers.push(page);
-    });
+    for page in line.split(',') {
+        page_numbers.push(parse_token(line_idx, line, page, "a page number")?);
+    }
+
+    Ok(fix_order(page_numbers, rules))
+}
 
+/// Moves the pages of the update into the correct order, returns whether they already were in it
+fn fix_order(page_numbers: &mut Vec<u64>, rules: &HashMap<u64, Vec<u64>>) -> bool {
     let mut was_in_correct_order = true;
     'outer: for idx in 0..page_numbers.len() {
         let page_number = page_numbers[idx];
@@ -45,7 +56,48 @@ fn process_update(line: &str, page_numbers: &mut Vec<u64>, rules: &HashMap<u64,
     was_in_correct_order
 }
 
-pub fn solve_puzzle<R: Read>(src: R) -> (u64, u64) {
+/// Ordering rules of the pages along with the updates that have to be printed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintQueue {
+    /// Pages that have to be printed after the page they are keyed by
+    rules: HashMap<u64, Vec<u64>>,
+    updates: Vec<Vec<u64>>,
+}
+
+pub fn parse_print_queue(src: &str) -> Result<PrintQueue, ParseError> {
+    let mut queue = PrintQueue::default();
+    let mut is_processing_instructions = true;
+    for (line_idx, line) in src.lines().enumerate() {
+        if line.is_empty() {
+            is_processing_instructions = false;
+        } else if is_processing_instructions {
+            let (num_1, num_2) = process_rule(line_idx, line)?;
+            queue.rules.entry(num_1).or_default().push(num_2);
+        } else {
+            let update = line
+                .split(',')
+                .map(|page| parse_token(line_idx, line, page, "a page number"))
+                .collect::<Result<_, _>>()?;
+            queue.updates.push(update);
+        }
+    }
+    Ok(queue)
+}
+
+/// Sums the middle pages of the updates that were in the correct order, or of the fixed ones if `fixed` is set
+pub fn sum_middle_pages(queue: &PrintQueue, fixed: bool) -> u64 {
+    let mut page_numbers = Vec::new();
+    let mut sum = 0;
+    for update in queue.updates.iter() {
+        page_numbers.clone_from(update);
+        if fix_order(&mut page_numbers, &queue.rules) != fixed {
+            sum += page_numbers[page_numbers.len() / 2];
+        }
+    }
+    sum
+}
+
+pub fn solve_puzzle<R: Read>(src: R) -> Result<(u64, u64), InputError> {
     let reader = BufReader::new(src);
 
     // part 1
@@ -56,21 +108,21 @@ pub fn solve_puzzle<R: Read>(src: R) -> (u64, u64) {
     let mut rules: HashMap<u64, Vec<u64>> = HashMap::new();
     let mut is_processing_instructions = true;
     let mut page_numbers = Vec::new();
-    reader.lines().for_each(|line| {
-        let line = line.expect("error while reading a line from the file");
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
         if line.is_empty() {
             // We processed the last instruction, proceed to processing the updates
             is_processing_instructions = false;
-            return;
+            continue;
         }
         if is_processing_instructions {
-            let (num_1, num_2) = process_rule(&line);
+            let (num_1, num_2) = process_rule(line_idx, &line)?;
             rules.entry(num_1).or_default().push(num_2);
         } else {
             // Clear the vec from the previous update
             page_numbers.clear();
 
-            let was_in_correct_order = process_update(&line, &mut page_numbers, &rules);
+            let was_in_correct_order = process_update(line_idx, &line, &mut page_numbers, &rules)?;
 
             let middle_page = page_numbers[page_numbers.len() / 2];
 
@@ -80,30 +132,70 @@ pub fn solve_puzzle<R: Read>(src: R) -> (u64, u64) {
                 sum_of_middle_page_numbers_of_fixed_updates += middle_page;
             }
         }
-    });
+    }
 
-    (
+    Ok((
         sum_of_middle_page_numbers_of_correct_updates,
         sum_of_middle_page_numbers_of_fixed_updates,
-    )
+    ))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = PrintQueue;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_print_queue(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        sum_middle_pages(input, false)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        sum_middle_pages(input, true)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{process_rule, solve_puzzle};
+    use common::ParseError;
+
+    use crate::{parse_print_queue, process_rule, solve_puzzle, sum_middle_pages};
 
     #[test]
     fn parses_rule_correctly() {
         let line = "77|123";
-        assert_eq!(process_rule(line), (77, 123));
+        assert_eq!(process_rule(0, line), Ok((77, 123)));
+    }
+
+    #[test]
+    fn reports_invalid_rules() {
+        assert_eq!(
+            process_rule(3, "77-123"),
+            Err(ParseError::new(4, 1, "77-123", "a rule in the 'X|Y' format"))
+        );
+        assert_eq!(
+            process_rule(3, "77|12x"),
+            Err(ParseError::new(4, 4, "12x", "a page number"))
+        );
     }
 
     #[test]
     fn solves_both_parts_correctly() {
         let input = "47|53\n97|13\n97|61\n97|47\n75|29\n61|13\n75|53\n29|13\n97|29\n53|29\n61|53\n97|53\n61|29\n47|13\n75|47\n97|75\n47|61\n75|61\n47|29\n75|13\n53|13\n\n75,47,61,53,29\n97,61,53,29,13\n75,29,13\n75,97,47,61,53\n61,13,29\n97,13,75,29,47";
 
-        let (part_1, part_2) = solve_puzzle(input.as_bytes());
+        let (part_1, part_2) = solve_puzzle(input.as_bytes()).unwrap();
         assert_eq!(part_1, 143);
         assert_eq!(part_2, 123);
+
+        let queue = parse_print_queue(input).unwrap();
+        assert_eq!(sum_middle_pages(&queue, false), 143);
+        assert_eq!(sum_middle_pages(&queue, true), 123);
     }
 }
@@ -1,55 +1,34 @@
-type Map = Vec<Vec<(bool, bool, bool)>>;
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum MovementDirection {
-    Up,
-    Right,
-    Down,
-    Left,
+use common::{InputError, ParseError, Solution};
+use grid::{Direction, Grid, Point};
+
+#[derive(Clone, Copy)]
+pub struct Cell {
+    is_free: bool,
+    is_visited: bool,
+    tested_for_loop: bool,
 }
 
-impl MovementDirection {
-    fn turn_90_degrees_right(self) -> MovementDirection {
-        match self {
-            MovementDirection::Up => MovementDirection::Right,
-            MovementDirection::Right => MovementDirection::Down,
-            MovementDirection::Down => MovementDirection::Left,
-            MovementDirection::Left => MovementDirection::Up,
-        }
-    }
-}
+type Map = Grid<Cell>;
 
 fn take_a_step(
     map: &Map,
-    mut position: (i64, i64),
-    mut direction: MovementDirection,
-    imaginary_obstacle_pos: Option<(i64, i64)>,
-) -> Option<((i64, i64), MovementDirection)> {
-    let (new_row, new_cell) = match direction {
-        MovementDirection::Up => (position.0 - 1, position.1),
-        MovementDirection::Right => (position.0, position.1 + 1),
-        MovementDirection::Down => (position.0 + 1, position.1),
-        MovementDirection::Left => (position.0, position.1 - 1),
-    };
-    let (cell, _, _) = map.get(new_row as usize).and_then(|row| row.get(new_cell as usize))?;
-
-    if *cell
-        && (imaginary_obstacle_pos.is_none() || imaginary_obstacle_pos.is_some_and(|pos| pos != (new_row, new_cell)))
-    {
-        position = (new_row, new_cell);
+    mut position: Point,
+    mut direction: Direction,
+    imaginary_obstacle_pos: Option<Point>,
+) -> Option<(Point, Direction)> {
+    let new_position = position.step(direction);
+    let cell = map.get(new_position)?;
+
+    if cell.is_free && imaginary_obstacle_pos.is_none_or(|pos| pos != new_position) {
+        position = new_position;
     } else {
-        direction = direction.turn_90_degrees_right();
+        direction = direction.turn_right();
     }
 
     Some((position, direction))
 }
 
-fn has_loop(
-    map: &Map,
-    starting_position: (i64, i64),
-    starting_movement_direction: MovementDirection,
-    obstacle_pos: (i64, i64),
-) -> bool {
+fn has_loop(map: &Map, starting_position: Point, starting_movement_direction: Direction, obstacle_pos: Point) -> bool {
     let mut steps = 0;
 
     let mut guards = [
@@ -80,78 +59,113 @@ fn has_loop(
     }
 }
 
-pub fn parse_map_and_start_position(src: &str) -> (Map, (i64, i64)) {
-    let mut guard_start_pos = (0, 0);
-    let map = src
-        .split("\n")
-        .enumerate()
-        .map(|(row_idx, line)| {
-            line.as_bytes()
-                .iter()
-                .enumerate()
-                .fold(Vec::with_capacity(line.len()), |mut level, (cell_idx, cell)| {
-                    if *cell == b'^' {
-                        guard_start_pos = (row_idx, cell_idx);
-                    }
-                    level.push((*cell != b'#', false, false));
-                    level
-                })
-        })
-        .collect::<Vec<_>>();
+pub fn parse_map_and_start_position(src: &str) -> Result<(Map, Point), ParseError> {
+    let map = Grid::try_parse(src, "'.', '#' or '^'", |cell| {
+        matches!(cell, b'.' | b'#' | b'^').then_some(cell)
+    })?;
+    let Some(guard_start_pos) = map.position(|cell| *cell == b'^') else {
+        return Err(ParseError::new(1, 1, "", "a guard ('^') somewhere on the map"));
+    };
+
+    let map = map.map(|cell| Cell {
+        is_free: *cell != b'#',
+        is_visited: false,
+        tested_for_loop: false,
+    });
 
-    (map, (guard_start_pos.0 as i64, guard_start_pos.1 as i64))
+    Ok((map, guard_start_pos))
 }
 
-pub fn solve_both_parts(map: &mut Map, starting_position: (i64, i64)) -> (u32, u32) {
+pub fn solve_both_parts(map: &mut Map, starting_position: Point) -> (u32, u32) {
     // Part 1
     let mut distinct_visited_cells = 0;
     // Part 2
     let mut loops = 0;
 
     let mut guard_pos = starting_position;
-    let mut movement_direction = MovementDirection::Up;
-    loop {
-        let Some(((new_row, new_cell), new_direction)) = take_a_step(map, guard_pos, movement_direction, None) else {
-            // Leave map
-            break;
-        };
-
+    let mut movement_direction = Direction::Up;
+    while let Some((new_position, new_direction)) = take_a_step(map, guard_pos, movement_direction, None) {
         let encountered_obstacle = new_direction != movement_direction;
         if !encountered_obstacle {
-            // This is safe to do as we would have returned above if any index was out of bounds
-            let (_, is_cell_visited, tested_for_loop) = &mut map[new_row as usize][new_cell as usize];
             // Part 1
-            if !*is_cell_visited {
-                *is_cell_visited = true;
+            if !map[new_position].is_visited {
+                map[new_position].is_visited = true;
                 distinct_visited_cells += 1;
             }
             // Part 2
-            if !*tested_for_loop {
-                *tested_for_loop = true;
-                if has_loop(map, guard_pos, movement_direction, (new_row, new_cell)) {
+            if !map[new_position].tested_for_loop {
+                map[new_position].tested_for_loop = true;
+                if has_loop(map, guard_pos, movement_direction, new_position) {
                     loops += 1;
                 }
             }
         }
 
-        guard_pos = (new_row, new_cell);
+        guard_pos = new_position;
         movement_direction = new_direction;
     }
 
     (distinct_visited_cells, loops)
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    // The loops of part 2 can only be placed on the path found in part 1
+    const SHARED_SOLVER: bool = true;
+
+    type Input = (Map, Point);
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_map_and_start_position(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        Self::solve(input).0
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        Self::solve(input).1
+    }
+
+    fn solve((map, start_pos): &Self::Input) -> (Self::Answer1, Self::Answer2) {
+        // Solving marks the visited cells, so work on a copy of the map
+        solve_both_parts(&mut map.clone(), *start_pos)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use common::ParseError;
+
     use crate::{parse_map_and_start_position, solve_both_parts};
 
     #[test]
     fn correctly_solves_both_parts() {
         let test_input = "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...";
-        let (mut map, start_pos) = parse_map_and_start_position(test_input);
+        let (mut map, start_pos) = parse_map_and_start_position(test_input).unwrap();
 
         let (part_1, part_2) = solve_both_parts(&mut map, start_pos);
         assert_eq!(part_1, 41);
         assert_eq!(part_2, 6);
     }
+
+    #[test]
+    fn rejects_invalid_maps() {
+        assert_eq!(
+            parse_map_and_start_position("..#\n.^. \n...").err(),
+            Some(ParseError::new(2, 4, " ", "end of the row"))
+        );
+        assert_eq!(
+            parse_map_and_start_position("..#\n.^x").err(),
+            Some(ParseError::new(2, 3, "x", "'.', '#' or '^'"))
+        );
+        assert_eq!(
+            parse_map_and_start_position("..#\n...").err(),
+            Some(ParseError::new(1, 1, "", "a guard ('^') somewhere on the map"))
+        );
+    }
 }
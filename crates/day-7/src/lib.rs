@@ -1,5 +1,7 @@
 use std::io::{BufRead as _, BufReader, Read};
 
+use common::{parse_token, InputError, ParseError, Solution};
+
 #[derive(Default, Clone, Copy)]
 pub enum Operation {
     #[default]
@@ -56,64 +58,155 @@ impl OperationSet {
     }
 }
 
-pub fn solve_both_parts<R: Read>(src: R) -> [u64; 2] {
+/// Parses the numbers of the equation into `numbers`, returning the test value
+fn parse_equation(line_idx: usize, line: &str, numbers: &mut Vec<u64>) -> Result<u64, ParseError> {
+    let Some((test_value, equation)) = line.split_once(':') else {
+        return Err(ParseError::at_token(
+            line_idx,
+            line,
+            line,
+            "an equation in the 'X: Y Z' format",
+        ));
+    };
+    let test_value = parse_token(line_idx, line, test_value, "a test value")?;
+
+    numbers.clear();
+    for number in equation.trim().split(' ') {
+        numbers.push(parse_token(line_idx, line, number, "a number")?);
+    }
+
+    Ok(test_value)
+}
+
+/// An equation whose operators have to be found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    pub test_value: u64,
+    pub numbers: Vec<u64>,
+}
+
+pub fn parse_equations(src: &str) -> Result<Vec<Equation>, ParseError> {
+    src.lines()
+        .enumerate()
+        .map(|(line_idx, line)| {
+            let mut numbers = Vec::new();
+            let test_value = parse_equation(line_idx, line, &mut numbers)?;
+            Ok(Equation { test_value, numbers })
+        })
+        .collect()
+}
+
+/// Returns whether some combination of the operators produces the test value
+fn is_solvable(test_value: u64, numbers: &[u64], with_concat: bool) -> bool {
+    let mut operations = OperationSet::new(numbers.len(), with_concat);
+    while operations.to_next_set() {
+        let result = numbers[1..]
+            .iter()
+            .enumerate()
+            .fold(numbers[0], |mut acc, (idx, number)| {
+                let operation = operations.get_operation_at_idx(idx);
+                match operation {
+                    Operation::Add => acc += number,
+                    Operation::Mul => {
+                        acc *= number;
+                    }
+                    Operation::Concat => acc = concat(acc, *number),
+                }
+                acc
+            });
+        if result == test_value {
+            return true;
+        }
+    }
+    false
+}
+
+/// Sums the test values of the equations that can be solved, `with_concat` enables the concatenation operator
+pub fn calibration_result(equations: &[Equation], with_concat: bool) -> u64 {
+    equations
+        .iter()
+        .filter(|equation| is_solvable(equation.test_value, &equation.numbers, with_concat))
+        .map(|equation| equation.test_value)
+        .sum()
+}
+
+pub fn solve_both_parts<R: Read>(src: R) -> Result<[u64; 2], InputError> {
     let reader = BufReader::new(src);
 
     let mut solutions = [0, 0];
     let mut numbers = Vec::new();
-    for line in reader.lines() {
-        let line = line.expect("error while reading a line");
-        let (test_value, equation) = line.split_once(':').expect("malformed input");
-        let test_value: u64 = test_value.parse().expect("not a valid test value");
-
-        numbers.clear();
-        for number in equation.trim().split(' ') {
-            let number: u64 = number.parse().expect("invalid number in equation");
-            numbers.push(number);
-        }
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let test_value = parse_equation(line_idx, &line, &mut numbers)?;
 
-        'outer: for (idx, solution) in solutions.iter_mut().enumerate() {
-            let mut operations = OperationSet::new(numbers.len(), idx != 0);
-            while operations.to_next_set() {
-                let result = numbers[1..]
-                    .iter()
-                    .enumerate()
-                    .fold(numbers[0], |mut acc, (idx, number)| {
-                        let operation = operations.get_operation_at_idx(idx);
-                        match operation {
-                            Operation::Add => acc += number,
-                            Operation::Mul => {
-                                acc *= number;
-                            }
-                            Operation::Concat => acc = concat(acc, *number),
-                        }
-                        acc
-                    });
-                if result == test_value {
-                    *solution += test_value;
-                    continue 'outer;
-                }
+        for (idx, solution) in solutions.iter_mut().enumerate() {
+            if is_solvable(test_value, &numbers, idx != 0) {
+                *solution += test_value;
             }
         }
     }
-    solutions
+    Ok(solutions)
 }
 
 fn concat(a: u64, b: u64) -> u64 {
     a * 10u64.pow(b.ilog10() + 1) + b
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<Equation>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_equations(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        calibration_result(input, false)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        calibration_result(input, true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::solve_both_parts;
+    use common::{InputError, ParseError};
+
+    use crate::{calibration_result, parse_equations, solve_both_parts};
 
     #[test]
     fn correctly_solves_both_parts() {
         let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20";
 
-        let [part_1, part_2] = solve_both_parts(input.as_bytes());
+        let [part_1, part_2] = solve_both_parts(input.as_bytes()).unwrap();
 
         assert_eq!(part_1, 3749);
         assert_eq!(part_2, 11387);
+
+        let equations = parse_equations(input).unwrap();
+        assert_eq!(calibration_result(&equations, false), 3749);
+        assert_eq!(calibration_result(&equations, true), 11387);
+    }
+
+    #[test]
+    fn reports_malformed_equations() {
+        let Err(InputError::Parse(error)) = solve_both_parts("190: 10 19\n3267 81 40 27".as_bytes()) else {
+            panic!("input should be rejected");
+        };
+        assert_eq!(
+            error,
+            ParseError::new(2, 1, "3267 81 40 27", "an equation in the 'X: Y Z' format")
+        );
+
+        let Err(InputError::Parse(error)) = solve_both_parts("190: 10 1a".as_bytes()) else {
+            panic!("input should be rejected");
+        };
+        assert_eq!(error, ParseError::new(1, 9, "1a", "a number"));
     }
 }
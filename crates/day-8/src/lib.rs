@@ -1,11 +1,23 @@
 use std::collections::{HashMap, HashSet};
-use std::io::{BufRead as _, BufReader, Read};
 
-type Antennas = HashMap<u8, Vec<(i64, i64)>>;
+use common::{InputError, ParseError, Solution};
+use grid::{Grid, Point};
 
-pub fn solve(antennas: &Antennas, map_bounds: (i64, i64), part_2: bool) -> usize {
-    let mut antinodes = HashSet::<(i64, i64)>::new();
-    for (_, antenna_positions) in antennas.iter() {
+type Antennas = HashMap<u8, Vec<Point>>;
+
+fn find_antennas(map: &Grid<u8>) -> Antennas {
+    let mut antennas: Antennas = HashMap::new();
+    for (position, value) in map.iter() {
+        if *value != b'.' {
+            antennas.entry(*value).or_default().push(position);
+        }
+    }
+    antennas
+}
+
+pub fn solve(map: &Grid<u8>, part_2: bool) -> usize {
+    let mut antinodes = HashSet::<Point>::new();
+    for (_, antenna_positions) in find_antennas(map).iter() {
         for (idx, position) in antenna_positions.iter().enumerate() {
             for second_antenna_position in antenna_positions[idx + 1..].iter() {
                 if part_2 {
@@ -14,47 +26,29 @@ pub fn solve(antennas: &Antennas, map_bounds: (i64, i64), part_2: bool) -> usize
                     antinodes.insert(*second_antenna_position);
                 }
 
-                let distance = (
-                    second_antenna_position.0 - position.0,
-                    second_antenna_position.1 - position.1,
-                );
-
-                let mut topmost_antinode_pos = (position.0 - distance.0, position.1 - distance.1);
-                while topmost_antinode_pos.0 >= 0
-                    && topmost_antinode_pos.0 < map_bounds.0
-                    && topmost_antinode_pos.1 >= 0
-                    && topmost_antinode_pos.1 < map_bounds.1
-                {
+                let distance = *second_antenna_position - *position;
+
+                let mut topmost_antinode_pos = *position - distance;
+                while map.contains(topmost_antinode_pos) {
                     antinodes.insert(topmost_antinode_pos);
                     if !part_2 {
                         // Run only once in part 2
                         break;
                     } else {
                         // Proceed to the next antinode in a line
-                        topmost_antinode_pos =
-                            (topmost_antinode_pos.0 - distance.0, topmost_antinode_pos.1 - distance.1);
+                        topmost_antinode_pos = topmost_antinode_pos - distance;
                     }
                 }
 
-                let mut bottommost_antinode_pos = (
-                    second_antenna_position.0 + distance.0,
-                    second_antenna_position.1 + distance.1,
-                );
-                while bottommost_antinode_pos.0 >= 0
-                    && bottommost_antinode_pos.0 < map_bounds.0
-                    && bottommost_antinode_pos.1 >= 0
-                    && bottommost_antinode_pos.1 < map_bounds.1
-                {
+                let mut bottommost_antinode_pos = *second_antenna_position + distance;
+                while map.contains(bottommost_antinode_pos) {
                     antinodes.insert(bottommost_antinode_pos);
                     if !part_2 {
                         // Run only once in part 2
                         break;
                     } else {
                         // Proceed to the next antinode in a line
-                        bottommost_antinode_pos = (
-                            bottommost_antinode_pos.0 + distance.0,
-                            bottommost_antinode_pos.1 + distance.1,
-                        );
+                        bottommost_antinode_pos = bottommost_antinode_pos + distance;
                     }
                 }
             }
@@ -64,27 +58,32 @@ pub fn solve(antennas: &Antennas, map_bounds: (i64, i64), part_2: bool) -> usize
     antinodes.len()
 }
 
-pub fn parse<R: Read>(src: R) -> (Antennas, (i64, i64)) {
-    let reader = BufReader::new(src);
+pub fn parse(src: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::try_parse(src, "'.' or an antenna frequency (a letter or a digit)", |cell| {
+        (cell == b'.' || cell.is_ascii_alphanumeric()).then_some(cell)
+    })
+}
 
-    let mut antennas: Antennas = HashMap::new();
-    let mut map_bounds = (0, 0);
-    for (row, line) in reader.lines().enumerate() {
-        let line = line.expect("error while reading a line");
+pub struct Day8;
 
-        line.as_bytes().iter().enumerate().for_each(|(cell, value)| {
-            if *value != b'.' {
-                antennas.entry(*value).or_default().push((row as i64, cell as i64));
-            }
-            if cell >= map_bounds.1 as usize {
-                map_bounds.1 += 1;
-            }
-        });
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-        map_bounds.0 += 1;
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse(input)?)
     }
 
-    (antennas, map_bounds)
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        solve(input, false)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Answer2 {
+        solve(input, true)
+    }
 }
 
 #[cfg(test)]
@@ -95,12 +94,12 @@ mod tests {
     fn correctly_solves_both_parts() {
         let test_input = "............\n........0...\n.....0......\n.......0....\n....0.......\n......A.....\n............\n............\n........A...\n.........A..\n............\n............";
 
-        let (antennas, map_bounds) = parse(test_input.as_bytes());
+        let map = parse(test_input).unwrap();
 
-        let part_1 = solve(&antennas, map_bounds, false);
+        let part_1 = solve(&map, false);
         assert_eq!(part_1, 14);
 
-        let part_2 = solve(&antennas, map_bounds, true);
+        let part_2 = solve(&map, true);
         assert_eq!(part_2, 34);
     }
 }
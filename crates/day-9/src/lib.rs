@@ -1,6 +1,11 @@
 use std::collections::VecDeque;
 
-pub fn part_1(mut data: Vec<Option<usize>>, mut free_slots: VecDeque<(usize, usize)>) -> usize {
+use common::{InputError, ParseError, Solution};
+
+type DiskMap = Vec<Option<usize>>;
+type FreeSlots = VecDeque<(usize, usize)>;
+
+pub fn part_1(mut data: DiskMap, mut free_slots: FreeSlots) -> usize {
     let (mut free_slot_start, mut free_slot_end) = free_slots.pop_front().expect("not a single free slot?");
     for idx in (0..data.len()).rev() {
         if free_slot_start == free_slot_end {
@@ -26,7 +31,7 @@ pub fn part_1(mut data: Vec<Option<usize>>, mut free_slots: VecDeque<(usize, usi
         .fold(0, |acc, (idx, file)| acc + idx * file.unwrap())
 }
 
-pub fn part_2(mut data: Vec<Option<usize>>, mut free_slots: VecDeque<(usize, usize)>) -> usize {
+pub fn part_2(mut data: DiskMap, mut free_slots: FreeSlots) -> usize {
     let mut inside_file = None;
     for idx in (0..data.len()).rev() {
         let Some(item) = data[idx] else {
@@ -66,10 +71,13 @@ pub fn part_2(mut data: Vec<Option<usize>>, mut free_slots: VecDeque<(usize, usi
         .fold(0, |acc, (idx, file)| acc + idx * file.unwrap())
 }
 
-pub fn parse_input(input: &str) -> (Vec<Option<usize>>, VecDeque<(usize, usize)>) {
-    input.chars().enumerate().fold(
+pub fn parse_input(input: &str) -> Result<(DiskMap, FreeSlots), ParseError> {
+    // The disk map is a single line, which may be terminated by a line break
+    let input = input.strip_suffix('\n').unwrap_or(input);
+
+    input.char_indices().enumerate().try_fold(
         (Vec::new(), VecDeque::new()),
-        |(mut acc, mut free_slots), (idx, num)| {
+        |(mut acc, mut free_slots), (idx, (char_idx, num))| {
             let value = if idx % 2 == 0 {
                 Some(idx / 2 + idx % 2)
             } else {
@@ -77,7 +85,10 @@ pub fn parse_input(input: &str) -> (Vec<Option<usize>>, VecDeque<(usize, usize)>
                 None
             };
 
-            let size = num.to_digit(10).expect("Invalid input");
+            let Some(size) = num.to_digit(10) else {
+                let token = &input[char_idx..char_idx + num.len_utf8()];
+                return Err(ParseError::at_token(0, input, token, "a block size (a digit)"));
+            };
 
             if size > 0 && idx % 2 == 1 {
                 free_slots.push_back((acc.len(), acc.len() + size as usize))
@@ -85,23 +96,55 @@ pub fn parse_input(input: &str) -> (Vec<Option<usize>>, VecDeque<(usize, usize)>
 
             acc.extend((0..size).map(|_| value));
 
-            (acc, free_slots)
+            Ok((acc, free_slots))
         },
     )
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = (DiskMap, FreeSlots);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, InputError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1((data, free_slots): &Self::Input) -> Self::Answer1 {
+        part_1(data.clone(), free_slots.clone())
+    }
+
+    fn part_2((data, free_slots): &Self::Input) -> Self::Answer2 {
+        part_2(data.clone(), free_slots.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use common::ParseError;
+
     use crate::{parse_input, part_1, part_2};
 
     #[test]
     fn correctly_calculates_both_parts() {
         let test_input = "2333133121414131402";
-        let (data, free_slots) = parse_input(test_input);
+        let (data, free_slots) = parse_input(test_input).unwrap();
 
         let part_1 = part_1(data.clone(), free_slots.clone());
         assert_eq!(part_1, 1928);
         let part_2 = part_2(data, free_slots);
         assert_eq!(part_2, 2858);
     }
+
+    #[test]
+    fn reports_stray_carriage_return() {
+        assert_eq!(
+            parse_input("2333\r\n").err(),
+            Some(ParseError::new(1, 5, "\r", "a block size (a digit)"))
+        );
+    }
 }
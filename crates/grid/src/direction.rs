@@ -0,0 +1,76 @@
+use crate::Point;
+
+/// A movement direction on a grid, where `Up` decreases the row index
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Directions to the 4 orthogonal neighbours, in clockwise order
+    pub const CARDINAL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// Directions to the 4 diagonal neighbours, in clockwise order
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    /// Directions to all 8 neighbours, in clockwise order
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Returns the offset that has to be added to a point to move in this direction
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(-1, 0),
+            Direction::UpRight => Point::new(-1, 1),
+            Direction::Right => Point::new(0, 1),
+            Direction::DownRight => Point::new(1, 1),
+            Direction::Down => Point::new(1, 0),
+            Direction::DownLeft => Point::new(1, -1),
+            Direction::Left => Point::new(0, -1),
+            Direction::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// Rotates the direction 90 degrees clockwise
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Rotates the direction 90 degrees counter-clockwise
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Rotates the direction clockwise by the given number of 45 degree steps
+    fn rotate(self, steps: usize) -> Direction {
+        let idx = Direction::ALL
+            .iter()
+            .position(|direction| *direction == self)
+            .expect("all directions are listed");
+        Direction::ALL[(idx + steps) % Direction::ALL.len()]
+    }
+}
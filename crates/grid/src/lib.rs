@@ -0,0 +1,226 @@
+mod direction;
+mod point;
+
+use std::ops::{Index, IndexMut};
+
+use common::ParseError;
+pub use direction::Direction;
+pub use point::Point;
+
+/// A rectangular 2D grid stored in row-major order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of the given size with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a grid from text, converting each byte of every line into a cell
+    pub fn parse(text: &str, mut convert: impl FnMut(u8) -> T) -> Result<Self, ParseError> {
+        Grid::try_parse(text, "", |cell| Some(convert(cell)))
+    }
+
+    /// Parses a grid from text, converting each byte of every line into a cell.
+    ///
+    /// Fails if any of the bytes can't be converted, in which case `expected` describes the valid cells.
+    pub fn try_parse(text: &str, expected: &str, mut convert: impl FnMut(u8) -> Option<T>) -> Result<Self, ParseError> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (row_idx, line) in text.lines().enumerate() {
+            if row_idx == 0 {
+                width = line.len();
+            } else if line.len() < width {
+                return Err(ParseError::at_end_of_line(
+                    row_idx,
+                    line,
+                    format!("{} cells in a row", width),
+                ));
+            } else if line.len() > width {
+                let extra_cells = line.get(width..).unwrap_or(line);
+                return Err(ParseError::at_token(row_idx, line, extra_cells, "end of the row"));
+            }
+
+            for (cell_idx, cell) in line.bytes().enumerate() {
+                let Some(cell) = convert(cell) else {
+                    let (char_idx, character) = line
+                        .char_indices()
+                        .take_while(|(char_idx, _)| *char_idx <= cell_idx)
+                        .last()
+                        .expect("line has at least one character");
+                    let token = &line[char_idx..char_idx + character.len_utf8()];
+                    return Err(ParseError::at_token(row_idx, line, token, expected));
+                };
+                cells.push(cell);
+            }
+            height += 1;
+        }
+
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Checks whether the point is within the bounds of the grid
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.height as i64).contains(&point.row) && (0..self.width as i64).contains(&point.col)
+    }
+
+    fn cell_idx(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.row as usize * self.width + point.col as usize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cell_idx(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cell_idx(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Returns all points of the grid in row-major order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |idx| Point::new((idx / width) as i64, (idx % width) as i64))
+    }
+
+    /// Returns all cells of the grid together with their positions in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Returns the neighbours of the point in the given directions, skipping the ones outside of the grid
+    pub fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        directions.iter().filter_map(move |direction| {
+            let neighbour = point.step(*direction);
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    /// Returns the position of the first cell (in row-major order) that matches the predicate
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(point, _)| point)
+    }
+
+    /// Creates a grid of the same size by converting every cell
+    pub fn map<U>(&self, convert: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(convert).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("point {:?} is outside of the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("point {:?} is outside of the grid", point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::ParseError;
+
+    use crate::{Direction, Grid, Point};
+
+    const TEST_GRID: &str = "abc\ndef\n";
+
+    #[test]
+    fn parses_grid_from_text() {
+        let grid = Grid::parse(TEST_GRID, |cell| cell).unwrap();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point::new(1, 2)], b'f');
+        assert_eq!(grid.position(|cell| *cell == b'e'), Some(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let error = Grid::parse("abc\nde", |cell| cell).unwrap_err();
+        assert_eq!(error, ParseError::new(2, 3, "", "3 cells in a row"));
+
+        let error = Grid::parse("abc\ndefg", |cell| cell).unwrap_err();
+        assert_eq!(error, ParseError::new(2, 4, "g", "end of the row"));
+    }
+
+    #[test]
+    fn rejects_invalid_cells() {
+        let error = Grid::try_parse("ab\nb\r", "'a' or 'b'", |cell| {
+            matches!(cell, b'a' | b'b').then_some(cell)
+        })
+        .unwrap_err();
+
+        assert_eq!(error, ParseError::new(2, 2, "\r", "'a' or 'b'"));
+    }
+
+    #[test]
+    fn checks_bounds_for_negative_points() {
+        let grid = Grid::parse(TEST_GRID, |cell| cell).unwrap();
+
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+    }
+
+    #[test]
+    fn skips_neighbours_outside_of_grid() {
+        let grid = Grid::parse(TEST_GRID, |cell| cell).unwrap();
+
+        let cardinal = grid
+            .neighbours(Point::new(0, 0), &Direction::CARDINAL)
+            .map(|(_, cell)| *cell)
+            .collect::<Vec<_>>();
+        assert_eq!(cardinal, [b'b', b'd']);
+
+        let all = grid
+            .neighbours(Point::new(0, 1), &Direction::ALL)
+            .map(|(_, cell)| *cell)
+            .collect::<Vec<_>>();
+        assert_eq!(all, [b'c', b'f', b'e', b'd', b'a']);
+    }
+
+    #[test]
+    fn rotates_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+    }
+}
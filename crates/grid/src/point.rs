@@ -0,0 +1,48 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::Direction;
+
+/// A signed position on a grid.
+///
+/// Points are allowed to be outside of the grid, so it's always safe to step in any direction and check the bounds
+/// afterwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub const fn new(row: i64, col: i64) -> Self {
+        Point { row, col }
+    }
+
+    /// Returns the adjacent point in the given direction
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Point {
+        Point::new(self.row * rhs, self.col * rhs)
+    }
+}